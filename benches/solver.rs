@@ -0,0 +1,52 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use glacier_slide::{Board, GameConfig};
+use std::hint::black_box;
+
+// Generation solves thousands of random boards, most of which turn out to be unsolvable, so the
+// benchmarks solve a batch of random boards rather than one hand-picked level.
+fn random_boards(difficulty: &str) -> (GameConfig, Vec<Board>) {
+    let config = GameConfig::get_config_from_difficulty(difficulty).unwrap();
+    let boards = (0..100)
+        .map(|seed| Board::generate_random_board(&config, seed))
+        .collect();
+    (config, boards)
+}
+
+fn solver(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve 100 random boards");
+
+    for difficulty in ["easy", "hard", "extreme"] {
+        let (config, boards) = random_boards(difficulty);
+        let max_depth = config.minimum_moves_required + 2;
+
+        group.bench_with_input(
+            BenchmarkId::new("slide table", difficulty),
+            &boards,
+            |b, boards| {
+                b.iter(|| {
+                    for board in boards {
+                        black_box(board.solve_with_max_depth(max_depth));
+                    }
+                })
+            },
+        );
+        group.bench_with_input(
+            BenchmarkId::new("reference", difficulty),
+            &boards,
+            |b, boards| {
+                b.iter(|| {
+                    for board in boards {
+                        // A freshly generated player rests on the start.
+                        let start = board.player.0;
+                        black_box(board.shortest_path_from_reference(start, max_depth));
+                    }
+                })
+            },
+        );
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, solver);
+criterion_main!(benches);
@@ -0,0 +1,236 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::slide_table::SlideTable;
+use super::{Board, Direction, Point};
+
+// Resting positions this close to the end (in tiles) count as near the goal.
+const DECOY_RADIUS: usize = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Reachability {
+    Solvable { moves_to_end: u32 },
+    // The player can rest here, but can never reach the end from here.
+    Trapped,
+}
+
+// Every resting position the player can reach from the start, and whether the end can still be
+// reached from it.
+#[derive(Debug)]
+pub struct BoardAnalysis {
+    positions: HashMap<Point, Reachability>,
+}
+
+impl BoardAnalysis {
+    // None for positions the player can never rest at.
+    pub fn classify(&self, position: Point) -> Option<Reachability> {
+        self.positions.get(&position).copied()
+    }
+
+    pub fn is_trapped(&self, position: Point) -> bool {
+        self.classify(position) == Some(Reachability::Trapped)
+    }
+
+    pub fn reachable_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    // Sorted top to bottom, then left to right.
+    pub fn trapped_positions(&self) -> Vec<Point> {
+        let mut trapped: Vec<Point> = self
+            .positions
+            .iter()
+            .filter(|(_, &class)| class == Reachability::Trapped)
+            .map(|(&p, _)| p)
+            .collect();
+        trapped.sort_by_key(|p| (p.row, p.col));
+        trapped
+    }
+}
+
+// Measures of how hard a solvable level is, beyond the length of its solution.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyMetrics {
+    pub optimal_moves: u32,
+    // Resting positions the player can reach from the start, including the start and the end.
+    pub reachable_states: u32,
+    // Average number of slides available from each reachable position (other than the end).
+    pub branching_factor: f64,
+    // Reachable positions the end can never be reached from.
+    pub trap_states: u32,
+    // Distinct shortest solutions.
+    pub optimal_solutions: u64,
+    // Positions close to the end (within DECOY_RADIUS tiles) that are still more than two moves
+    // away from it, or cannot reach it at all.
+    pub decoy_states: u32,
+    pub score: u32,
+}
+
+impl DifficultyMetrics {
+    // Ten points per move, plus a point per reachable position, two per trap and three per decoy.
+    // Extra choices at each position add up to 30 points. Every additional optimal solution makes
+    // the level easier to stumble through, and takes 5 points off (up to 50).
+    fn score(&self) -> u32 {
+        let base = 10 * self.optimal_moves
+            + self.reachable_states
+            + 2 * self.trap_states
+            + 3 * self.decoy_states
+            + (10.0 * (self.branching_factor - 1.0)).round().max(0.0) as u32;
+        let shortcuts = 5 * (self.optimal_solutions.saturating_sub(1).min(10) as u32);
+        base.saturating_sub(shortcuts)
+    }
+}
+
+impl Board {
+    // None for boards that cannot be solved.
+    pub fn difficulty_metrics(&self) -> Option<DifficultyMetrics> {
+        let analysis = self.analyze();
+        let end = self.layout.end.0;
+        let Some(Reachability::Solvable { moves_to_end }) = analysis.classify(self.layout.start.0)
+        else {
+            return None;
+        };
+
+        let distance = |p: Point| match analysis.classify(p) {
+            Some(Reachability::Solvable { moves_to_end }) => Some(moves_to_end),
+            _ => None,
+        };
+
+        let mut table = SlideTable::new(&self.layout);
+        let (mut slides, mut sliding_positions) = (0, 0);
+        for &position in analysis.positions.keys().filter(|&&p| p != end) {
+            sliding_positions += 1;
+            let cell = table.cell(position);
+            slides += Direction::ALL
+                .into_iter()
+                .filter(|&direction| table.slide(cell, direction).is_some())
+                .count();
+        }
+
+        let decoy_states = analysis
+            .positions
+            .keys()
+            .filter(|&&p| {
+                p != end && p.row.abs_diff(end.row) + p.col.abs_diff(end.col) <= DECOY_RADIUS
+            })
+            .filter(|&&p| distance(p).is_none_or(|d| d > 2))
+            .count();
+
+        let mut metrics = DifficultyMetrics {
+            optimal_moves: moves_to_end,
+            reachable_states: analysis.reachable_count() as u32,
+            branching_factor: if sliding_positions == 0 {
+                0.0
+            } else {
+                slides as f64 / f64::from(sliding_positions)
+            },
+            trap_states: analysis.trapped_positions().len() as u32,
+            optimal_solutions: self.count_optimal_solutions(),
+            decoy_states: decoy_states as u32,
+            score: 0,
+        };
+        metrics.score = metrics.score();
+        Some(metrics)
+    }
+
+    pub fn analyze(&self) -> BoardAnalysis {
+        let positions = self
+            .distance_map()
+            .into_iter()
+            .map(|(p, distance)| {
+                let class = match distance {
+                    Some(moves_to_end) => Reachability::Solvable { moves_to_end },
+                    None => Reachability::Trapped,
+                };
+                (p, class)
+            })
+            .collect();
+
+        BoardAnalysis { positions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::{
+        board_with_dead_end, board_with_two_solutions, simple_board, unsolvable_board,
+    };
+
+    #[test]
+    fn difficulty_metrics_for_board_with_dead_end() {
+        let board = board_with_dead_end();
+        let metrics = board.difficulty_metrics().unwrap();
+
+        assert_eq!(metrics.optimal_moves, 2);
+        assert_eq!(metrics.reachable_states, 10);
+        assert_eq!(metrics.trap_states, 6);
+        assert_eq!(metrics.optimal_solutions, 1);
+        // Only the dead end at [1, 4] is close to the end.
+        assert_eq!(metrics.decoy_states, 1);
+        // 19 slides from the 9 positions other than the end.
+        assert!((metrics.branching_factor - 19.0 / 9.0).abs() < 1e-9);
+        assert_eq!(metrics.score, 20 + 10 + 2 * 6 + 3 + 11);
+    }
+
+    #[test]
+    fn difficulty_metrics_count_every_optimal_solution() {
+        /*
+        WWWWW
+        S   W
+        W   W
+        W   W
+        WWWEW
+        The only 2 move solution is RD.
+        */
+        let board = Board::from_ascii("WWWWW\nS   W\nW   W\nW   W\nWWWEW").unwrap();
+        assert_eq!(board.difficulty_metrics().unwrap().optimal_solutions, 1);
+
+        let board = board_with_two_solutions();
+        let metrics = board.difficulty_metrics().unwrap();
+        assert_eq!(metrics.optimal_moves, 4);
+        assert_eq!(metrics.optimal_solutions, 2);
+    }
+
+    #[test]
+    fn difficulty_metrics_are_none_for_unsolvable_boards() {
+        let board = unsolvable_board();
+        assert!(board.difficulty_metrics().is_none());
+    }
+
+    #[test]
+    fn analyze_finds_trapped_positions() {
+        let board = board_with_dead_end();
+        let analysis = board.analyze();
+
+        assert_eq!(
+            analysis.classify(Point { col: 5, row: 2 }),
+            Some(Reachability::Solvable { moves_to_end: 2 })
+        );
+        assert!(analysis.is_trapped(Point { col: 4, row: 1 }));
+        assert_eq!(analysis.classify(Point { col: 3, row: 3 }), None);
+
+        let trapped = analysis.trapped_positions();
+        assert_eq!(
+            trapped,
+            vec![
+                Point { col: 1, row: 1 },
+                Point { col: 4, row: 1 },
+                Point { col: 1, row: 3 },
+                Point { col: 4, row: 3 },
+                Point { col: 1, row: 4 },
+                Point { col: 4, row: 4 },
+            ]
+        );
+    }
+
+    #[test]
+    fn solvable_board_without_traps() {
+        let board = simple_board();
+        let analysis = board.analyze();
+
+        assert!(analysis.trapped_positions().is_empty());
+        assert!(analysis.reachable_count() > 1);
+    }
+}
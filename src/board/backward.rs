@@ -0,0 +1,276 @@
+use rand::{seq::IndexedRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Board, Direction, Point};
+use crate::game_state::GameConfig;
+
+fn opposite(direction: Direction) -> Direction {
+    match direction {
+        Direction::Up => Direction::Down,
+        Direction::Down => Direction::Up,
+        Direction::Left => Direction::Right,
+        Direction::Right => Direction::Left,
+    }
+}
+
+fn perpendicular(direction: Direction) -> [Direction; 2] {
+    match direction {
+        Direction::Up | Direction::Down => [Direction::Left, Direction::Right],
+        Direction::Left | Direction::Right => [Direction::Up, Direction::Down],
+    }
+}
+
+// A level being built backwards from the end, one move at a time.
+struct Plan {
+    rows: usize,
+    cols: usize,
+    end: Point,
+    rocks: Vec<Vec<bool>>,
+    // Tiles the planned path slides over or stops on. These must stay free of rocks.
+    path: Vec<Vec<bool>>,
+    // Border walls the planned path stops against. The start cannot replace any of them.
+    border_stoppers: Vec<Point>,
+}
+
+impl Plan {
+    fn new(rows: usize, cols: usize, end: Point) -> Self {
+        let mut plan = Plan {
+            rows,
+            cols,
+            end,
+            rocks: vec![vec![false; cols]; rows],
+            path: vec![vec![false; cols]; rows],
+            border_stoppers: Vec::new(),
+        };
+        plan.path[end.row][end.col] = true;
+        plan
+    }
+
+    fn is_border(&self, p: Point) -> bool {
+        p.row == 0 || p.col == 0 || p.row == self.rows - 1 || p.col == self.cols - 1
+    }
+
+    // The next tile in `direction`, or None off the edge of the board.
+    fn neighbour(&self, p: Point, direction: Direction) -> Option<Point> {
+        match direction {
+            Direction::Up => p.row.checked_sub(1).map(|row| Point { row, ..p }),
+            Direction::Down => (p.row + 1 < self.rows).then_some(Point {
+                row: p.row + 1,
+                ..p
+            }),
+            Direction::Left => p.col.checked_sub(1).map(|col| Point { col, ..p }),
+            Direction::Right => (p.col + 1 < self.cols).then_some(Point {
+                col: p.col + 1,
+                ..p
+            }),
+        }
+    }
+
+    // Tiles a slide in `direction` could start from and stop at `to`, nearest first. The last one
+    // is on the border if nothing blocks the way back to it.
+    fn origins(&self, to: Point, direction: Direction) -> Vec<Point> {
+        let mut origins = Vec::new();
+        let mut current = to;
+        while let Some(previous) = self.neighbour(current, opposite(direction)) {
+            if self.rocks[previous.row][previous.col] {
+                break;
+            }
+            origins.push(previous);
+            if self.is_border(previous) {
+                break;
+            }
+            current = previous;
+        }
+        origins
+    }
+
+    // Whether a rock can be placed here, or something already stops the player here.
+    fn can_stop_at(&self, stopper: Point) -> bool {
+        if self.is_border(stopper) {
+            stopper != self.end
+        } else {
+            !self.path[stopper.row][stopper.col]
+        }
+    }
+
+    // Mark the tiles a slide from `from` to `to` passes over (including both ends).
+    fn add_slide(&mut self, from: Point, to: Point) {
+        for row in from.row.min(to.row)..=from.row.max(to.row) {
+            for col in from.col.min(to.col)..=from.col.max(to.col) {
+                self.path[row][col] = true;
+            }
+        }
+    }
+
+    // Plan the move before the one that slid `direction` into `to`: pick where it came to rest,
+    // and the rock (or wall) that stopped it there. Returns the new resting position and the
+    // direction of the move that reached it.
+    fn plan_previous_move(
+        &mut self,
+        to: Point,
+        direction: Direction,
+        rng: &mut impl Rng,
+    ) -> Option<(Point, Direction)> {
+        let mut choices = Vec::new();
+        for from in self.origins(to, direction) {
+            if self.is_border(from) {
+                continue;
+            }
+            for arrival in perpendicular(direction) {
+                if let Some(stopper) = self.neighbour(from, arrival) {
+                    if self.can_stop_at(stopper) {
+                        choices.push((from, arrival, stopper));
+                    }
+                }
+            }
+        }
+
+        let &(from, arrival, stopper) = choices.choose(rng)?;
+        self.add_slide(from, to);
+        if self.is_border(stopper) {
+            self.border_stoppers.push(stopper);
+        } else {
+            self.rocks[stopper.row][stopper.col] = true;
+        }
+        Some((from, arrival))
+    }
+
+    // The first move starts on the border, straight back along the line it slides in.
+    fn plan_start(&mut self, to: Point, direction: Direction) -> Option<Point> {
+        let start = *self.origins(to, direction).last()?;
+        if !self.is_border(start) || start == self.end || self.border_stoppers.contains(&start) {
+            return None;
+        }
+        self.add_slide(start, to);
+        Some(start)
+    }
+}
+
+// A random border tile (never a corner), and the direction a slide into it moves in.
+fn random_end(rows: usize, cols: usize, rng: &mut impl Rng) -> (Point, Direction) {
+    match rng.random_range(0..4) {
+        0 => (
+            Point {
+                col: rng.random_range(1..cols - 1),
+                row: 0,
+            },
+            Direction::Up,
+        ),
+        1 => (
+            Point {
+                col: rng.random_range(1..cols - 1),
+                row: rows - 1,
+            },
+            Direction::Down,
+        ),
+        2 => (
+            Point {
+                col: 0,
+                row: rng.random_range(1..rows - 1),
+            },
+            Direction::Left,
+        ),
+        _ => (
+            Point {
+                col: cols - 1,
+                row: rng.random_range(1..rows - 1),
+            },
+            Direction::Right,
+        ),
+    }
+}
+
+impl Board {
+    // Build a level by planning a path of minimum_moves_required moves backwards from the end,
+    // placing a rock wherever the path needs to stop. Rocks are then scattered over the rest of the
+    // board as usual. The plan can still be cut short by another route, so the result needs
+    // solving like any other board. None if the plan ran into a dead end.
+    pub(super) fn generate_backward_board(game_config: &GameConfig, seed: u64) -> Option<Self> {
+        assert!(game_config.cols >= 3 && game_config.rows >= 3);
+
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let cols = game_config.cols as usize;
+        let rows = game_config.rows as usize;
+
+        let (end, last_move) = random_end(rows, cols, &mut rng);
+        let mut plan = Plan::new(rows, cols, end);
+        let (mut to, mut direction) = (end, last_move);
+        for _ in 1..game_config.minimum_moves_required.max(1) {
+            (to, direction) = plan.plan_previous_move(to, direction, &mut rng)?;
+        }
+        let start = plan.plan_start(to, direction)?;
+
+        let mut rocks = Vec::new();
+        for col in 1..cols - 1 {
+            for row in 1..rows - 1 {
+                let planned_rock = plan.rocks[row][col];
+                let scattered_rock = !plan.path[row][col]
+                    && rng.random_range(1..=100) <= game_config.rock_probability;
+                if planned_rock || scattered_rock {
+                    rocks.push(Point { col, row });
+                }
+            }
+        }
+
+        let mut board = Board::new(rows, cols, start, end, rocks);
+        board.layout.seed = Some(seed);
+        Some(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GenerationStrategy;
+
+    #[test]
+    fn backward_board_can_follow_its_plan() {
+        let config = GameConfig {
+            minimum_moves_required: 10,
+            rock_probability: 5,
+            strategy: GenerationStrategy::Backward,
+            ..GameConfig::default()
+        };
+
+        let mut planned = 0;
+        for seed in 0..200 {
+            let Some(board) = Board::generate_backward_board(&config, seed) else {
+                continue;
+            };
+            planned += 1;
+            // The planned path (or a shortcut) always leads to the end.
+            let moves = board
+                .solve()
+                .steps
+                .expect("a planned board is solvable")
+                .len();
+            assert!(moves <= 10, "seed {seed} needed {moves} moves");
+        }
+        assert!(planned > 0);
+    }
+
+    #[test]
+    fn backward_generation_finds_long_levels_on_small_boards() {
+        // 7x7 playable tiles needing 14 moves. From this seed, random rocks first get there after
+        // more than 150,000 attempts.
+        let config = GameConfig {
+            cols: 9,
+            rows: 9,
+            minimum_moves_required: 14,
+            rock_probability: 5,
+            seed: Some(1),
+            max_attempts: 20_000,
+            strategy: GenerationStrategy::Backward,
+            ..GameConfig::default()
+        };
+
+        let board = Board::generate_solvable_board(&config, None).unwrap();
+        assert!(board.par().unwrap() >= 14);
+
+        let random = GameConfig {
+            strategy: GenerationStrategy::Random,
+            ..config
+        };
+        assert!(Board::generate_solvable_board(&random, None).is_err());
+    }
+}
@@ -24,6 +24,16 @@ impl Direction {
             Direction::Right => 'R',
         }
     }
+
+    pub fn from_char(c: char) -> Option<Direction> {
+        match c {
+            'U' => Some(Direction::Up),
+            'D' => Some(Direction::Down),
+            'L' => Some(Direction::Left),
+            'R' => Some(Direction::Right),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Direction {
@@ -40,12 +50,12 @@ impl Debug for Direction {
 
 #[derive(Debug, Clone)]
 pub struct Slide {
-    pub steps: u8,
+    pub steps: usize,
     pub direction: Direction,
 }
 
 impl Slide {
-    pub fn new(steps: u8, direction: Direction) -> Self {
+    pub fn new(steps: usize, direction: Direction) -> Self {
         Slide { steps, direction }
     }
 }
@@ -67,4 +77,13 @@ mod tests {
         assert_eq!(Direction::Left.as_char(), 'L');
         assert_eq!(Direction::Right.as_char(), 'R');
     }
+
+    #[test]
+    fn direction_from_char_round_trips() {
+        for d in Direction::ALL {
+            assert_eq!(Direction::from_char(d.as_char()), Some(d));
+        }
+        assert_eq!(Direction::from_char('u'), None);
+        assert_eq!(Direction::from_char('X'), None);
+    }
 }
@@ -0,0 +1,554 @@
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use rand::Rng;
+use thousands::Separable;
+use time_elapsed::{self, TimeElapsed};
+
+use super::{Board, Point, Tile};
+use crate::error::GlacierError;
+use crate::game_state::{GameConfig, GenerationStrategy};
+
+// How often progress is reported while a level generates.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+// How far a level generation has got, as reported to the progress callback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GenerationProgress {
+    pub attempts: u32,
+    pub max_attempts: u32,
+    pub elapsed: Duration,
+    pub time_budget: Option<Duration>,
+    // The longest shortest solution among the boards tried so far, including the ones that fell short.
+    pub best_moves: Option<u32>,
+}
+
+impl GenerationProgress {
+    fn new(game_config: &GameConfig) -> Self {
+        GenerationProgress {
+            attempts: 0,
+            max_attempts: game_config.max_attempts,
+            elapsed: Duration::ZERO,
+            time_budget: game_config.time_budget,
+            best_moves: None,
+        }
+    }
+
+    // How much of the attempt or time budget has been used (0.0 to 1.0), whichever is further along.
+    pub fn fraction(&self) -> f64 {
+        let by_attempts = f64::from(self.attempts) / f64::from(self.max_attempts.max(1));
+        let by_time = self.time_budget.map_or(0.0, |budget| {
+            self.elapsed.as_secs_f64() / budget.as_secs_f64().max(f64::EPSILON)
+        });
+        by_attempts.max(by_time).min(1.0)
+    }
+}
+
+// The outcome of one generation attempt.
+struct Attempt {
+    // The length of the board's shortest solution, if it has one.
+    moves: Option<u32>,
+    // The board, if its solution is in the accepted range of moves.
+    board: Option<Board>,
+    // Boards solved along the way, which count towards game_config.max_attempts. A climb solves
+    // one for every rock it moves.
+    boards_tried: u32,
+}
+
+fn out_of_time(started: Instant, game_config: &GameConfig) -> bool {
+    game_config
+        .time_budget
+        .is_some_and(|budget| started.elapsed() >= budget)
+}
+
+impl Board {
+    // Like generate_solvable_board_cancellable, but calls `on_progress` every so often while the
+    // level generates. Generation gives up once game_config.max_attempts boards have been tried, or
+    // once game_config.time_budget has passed.
+    pub fn generate_solvable_board_with_progress(
+        game_config: &GameConfig,
+        request_id_opt: Option<u64>,
+        cancelled: &AtomicBool,
+        mut on_progress: impl FnMut(&GenerationProgress),
+    ) -> Result<Self, GlacierError> {
+        let mut time: Option<TimeElapsed> = None;
+        let request_id = request_id_opt.unwrap_or(1);
+        // Each attempt uses its own seed (base + attempt index), so the seed stored in the
+        // resulting layout regenerates that exact board on the first attempt.
+        let base_seed = game_config.seed.unwrap_or_else(|| rand::rng().random());
+
+        if game_config.debug {
+            time = Some(time_elapsed::start("level generator"));
+        } else if !game_config.board_only {
+            tracing::info!(request_id, "Generating level {:?}.", &game_config);
+        }
+
+        let result = if game_config.threads > 1 {
+            let result =
+                Board::generate_in_parallel(game_config, base_seed, cancelled, &mut on_progress);
+            if let (Some(t), Ok(board)) = (time.as_mut(), &result) {
+                t.log_overall(format!(
+                    "Boards generated: {:9} (on {} threads)",
+                    board.generation_attempts.separate_with_commas(),
+                    game_config.threads
+                ));
+            }
+            result
+        } else {
+            Board::generate_sequentially(
+                game_config,
+                base_seed,
+                cancelled,
+                &mut on_progress,
+                time.as_mut(),
+            )
+        };
+
+        let mut board = match result {
+            Ok(board) => board,
+            Err(GlacierError::GenerationCancelled) => {
+                tracing::warn!(request_id, "Level generation cancelled.");
+                return Err(GlacierError::GenerationCancelled);
+            }
+            Err(err) => {
+                tracing::error!(request_id, "{}", err);
+                return Err(err);
+            }
+        };
+
+        tracing::info!(
+            request_id,
+            "Solvable level found after {} attempts.",
+            &board.generation_attempts
+        );
+
+        if game_config.board_only {
+            // After solving, replace the player's tile with Start in the layout,
+            // so JSON shows 'S' instead of 'P' in level layout.
+            let start: Point = board.layout.start.0;
+            board.layout.grid[start.row][start.col] = Tile::Start;
+        }
+
+        Ok(board)
+    }
+
+    // Build and solve the board for one attempt. A climb solves at most `boards_left` boards, and
+    // gives up early once `stop` returns true.
+    fn generation_attempt(
+        game_config: &GameConfig,
+        seed: u64,
+        boards_left: u32,
+        stop: &dyn Fn() -> bool,
+    ) -> Attempt {
+        let (board, boards_tried) = match game_config.strategy {
+            GenerationStrategy::Random => {
+                (Some(Board::generate_random_board(game_config, seed)), 1)
+            }
+            GenerationStrategy::Backward => (Board::generate_backward_board(game_config, seed), 1),
+            GenerationStrategy::HillClimb => {
+                let (board, solved) =
+                    Board::generate_climbed_board(game_config, seed, boards_left, stop);
+                (Some(board), solved)
+            }
+        };
+        let mut attempt = Attempt {
+            moves: None,
+            board: None,
+            boards_tried,
+        };
+        let Some(mut board) = board else {
+            return attempt;
+        };
+
+        let accepted = game_config.accepted_moves();
+        board.solution = Some(board.solve_with_max_depth(*accepted.end()));
+        attempt.moves = board.par();
+        let in_range = attempt
+            .moves
+            .is_some_and(|m| u16::try_from(m).is_ok_and(|m| accepted.contains(&m)));
+        if !in_range {
+            return attempt;
+        }
+
+        // Only boards that need enough moves are worth the full analysis.
+        board.layout.difficulty = board.difficulty_metrics();
+        let qualifies = board.layout.difficulty.as_ref().is_some_and(|d| {
+            (!game_config.unique_solution || d.optimal_solutions == 1)
+                && game_config
+                    .min_difficulty_score
+                    .is_none_or(|min| d.score >= min)
+        });
+
+        attempt.board = qualifies.then_some(board);
+        attempt
+    }
+
+    fn generate_sequentially(
+        game_config: &GameConfig,
+        base_seed: u64,
+        cancelled: &AtomicBool,
+        on_progress: &mut dyn FnMut(&GenerationProgress),
+        mut time: Option<&mut TimeElapsed>,
+    ) -> Result<Board, GlacierError> {
+        let started = Instant::now();
+        let mut last_report = started;
+        let mut progress = GenerationProgress::new(game_config);
+        let mut denominator: u32 = 1;
+        let stop = || cancelled.load(Ordering::Relaxed) || out_of_time(started, game_config);
+        // Attempts made so far. A climb can try many boards in one attempt, so this falls behind
+        // progress.attempts.
+        let mut attempt: u32 = 0;
+
+        loop {
+            if cancelled.load(Ordering::Relaxed) {
+                return Err(GlacierError::GenerationCancelled);
+            }
+
+            if progress.attempts >= game_config.max_attempts || out_of_time(started, game_config) {
+                return Err(GlacierError::GenerationExhausted {
+                    attempts: progress.attempts,
+                });
+            }
+
+            let seed = base_seed.wrapping_add(u64::from(attempt));
+            let boards_left = game_config.max_attempts - progress.attempts;
+            let outcome = Board::generation_attempt(game_config, seed, boards_left, &stop);
+            attempt += 1;
+            progress.attempts += outcome.boards_tried;
+            progress.best_moves = progress.best_moves.max(outcome.moves);
+
+            if progress.attempts >= denominator {
+                denominator = denominator.saturating_mul(10);
+
+                if let Some(t) = time.as_mut() {
+                    t.log_overall(format!(
+                        "Boards generated: {:9}",
+                        progress.attempts.separate_with_commas()
+                    ));
+                }
+            }
+
+            if let Some(mut board) = outcome.board {
+                board.generation_attempts = attempt;
+                return Ok(board);
+            }
+
+            if last_report.elapsed() >= PROGRESS_INTERVAL {
+                last_report = Instant::now();
+                progress.elapsed = started.elapsed();
+                on_progress(&progress);
+            }
+        }
+    }
+
+    // Spread the attempts across game_config.threads workers, with worker w trying attempts w,
+    // w + threads, w + 2 * threads and so on. The lowest qualifying attempt wins, so a seeded config
+    // produces the same board as generating on a single thread. Progress is reported from the
+    // calling thread while the workers run.
+    fn generate_in_parallel(
+        game_config: &GameConfig,
+        base_seed: u64,
+        cancelled: &AtomicBool,
+        on_progress: &mut dyn FnMut(&GenerationProgress),
+    ) -> Result<Board, GlacierError> {
+        // Library callers can ask for any number of threads, so keep it to something u32 can step by.
+        let threads = u32::try_from(game_config.threads)
+            .unwrap_or(u32::MAX)
+            .max(1);
+        let started = Instant::now();
+        // The lowest qualifying attempt found so far. Every worker stops once all of its remaining
+        // attempts are higher, so the others stop as soon as one finds a board.
+        let best = AtomicU32::new(u32::MAX);
+        let attempts = AtomicU32::new(0);
+        // 0 until a board with any solution has been tried.
+        let best_moves = AtomicU32::new(0);
+        let caller = thread::current();
+
+        let found = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let (best, attempts, best_moves, caller) =
+                        (&best, &attempts, &best_moves, caller.clone());
+                    scope.spawn(move || {
+                        let mut attempt = worker;
+                        let mut found = None;
+                        // Also checked between the boards of a climb, so climbs stop as soon as
+                        // a lower attempt wins.
+                        let stop = |attempt: u32| {
+                            attempt > best.load(Ordering::Relaxed)
+                                || cancelled.load(Ordering::Relaxed)
+                                || out_of_time(started, game_config)
+                        };
+                        // Each attempt can try many boards, so the workers also share the budget.
+                        // Climbs running at the same time can take it slightly over.
+                        let boards_left = || {
+                            game_config
+                                .max_attempts
+                                .saturating_sub(attempts.load(Ordering::Relaxed))
+                        };
+                        while attempt < game_config.max_attempts
+                            && boards_left() > 0
+                            && !stop(attempt)
+                        {
+                            let seed = base_seed.wrapping_add(u64::from(attempt));
+                            let outcome = Board::generation_attempt(
+                                game_config,
+                                seed,
+                                boards_left(),
+                                &|| stop(attempt),
+                            );
+                            attempts.fetch_add(outcome.boards_tried, Ordering::Relaxed);
+                            if let Some(moves) = outcome.moves {
+                                best_moves.fetch_max(moves, Ordering::Relaxed);
+                            }
+
+                            if let Some(board) = outcome.board {
+                                best.fetch_min(attempt, Ordering::Relaxed);
+                                found = Some((attempt, board));
+                                break;
+                            }
+                            attempt = attempt.saturating_add(threads);
+                        }
+                        // Wake the caller, so it notices without waiting for the next report.
+                        caller.unpark();
+                        found
+                    })
+                })
+                .collect();
+
+            while !workers.iter().all(|worker| worker.is_finished()) {
+                thread::park_timeout(PROGRESS_INTERVAL);
+                if started.elapsed() >= PROGRESS_INTERVAL {
+                    let best_moves = best_moves.load(Ordering::Relaxed);
+                    on_progress(&GenerationProgress {
+                        attempts: attempts.load(Ordering::Relaxed),
+                        elapsed: started.elapsed(),
+                        best_moves: (best_moves > 0).then_some(best_moves),
+                        ..GenerationProgress::new(game_config)
+                    });
+                }
+            }
+
+            workers
+                .into_iter()
+                .filter_map(|worker| worker.join().expect("generation worker panicked"))
+                .min_by_key(|(attempt, _)| *attempt)
+        });
+
+        match found {
+            Some((attempt, mut board)) => {
+                board.generation_attempts = attempt + 1;
+                Ok(board)
+            }
+            None if cancelled.load(Ordering::Relaxed) => Err(GlacierError::GenerationCancelled),
+            None => Err(GlacierError::GenerationExhausted {
+                attempts: attempts.into_inner(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_generation_matches_single_threaded_generation() {
+        for seed in [1, 99, 1234] {
+            let config = GameConfig {
+                seed: Some(seed),
+                board_only: true,
+                ..GameConfig::get_config_from_difficulty("medium").unwrap()
+            };
+            let single = Board::generate_solvable_board(&config, None).unwrap();
+            let parallel = Board::generate_solvable_board(
+                &GameConfig {
+                    threads: 4,
+                    ..config.clone()
+                },
+                None,
+            )
+            .unwrap();
+
+            assert_eq!(single.get_layout_json(), parallel.get_layout_json());
+            assert_eq!(single.generation_attempts, parallel.generation_attempts);
+        }
+    }
+
+    #[test]
+    fn parallel_generation_stops_when_cancelled() {
+        let config = GameConfig {
+            threads: 4,
+            ..GameConfig::default()
+        };
+        let cancelled = AtomicBool::new(true);
+
+        let result = Board::generate_solvable_board_cancellable(&config, None, &cancelled);
+        assert!(matches!(result, Err(GlacierError::GenerationCancelled)));
+    }
+
+    #[test]
+    fn generation_gives_up_after_max_attempts() {
+        // No 3x3 board needs more than a handful of moves.
+        for threads in [1, 3] {
+            let config = GameConfig {
+                cols: 5,
+                rows: 5,
+                minimum_moves_required: 30,
+                max_attempts: 500,
+                threads,
+                ..GameConfig::default()
+            };
+            let result = Board::generate_solvable_board(&config, None);
+            assert!(matches!(
+                result,
+                Err(GlacierError::GenerationExhausted { attempts: 500 })
+            ));
+        }
+    }
+
+    #[test]
+    fn every_board_a_climb_solves_counts_towards_max_attempts() {
+        // No 3x3 board needs 8 moves, so every climb runs until it gives up.
+        for threads in [1, 3] {
+            let config = GameConfig {
+                cols: 5,
+                rows: 5,
+                minimum_moves_required: 8,
+                strategy: GenerationStrategy::HillClimb,
+                max_attempts: 2_000,
+                threads,
+                ..GameConfig::default()
+            };
+            let result = Board::generate_solvable_board(&config, None);
+            let Err(GlacierError::GenerationExhausted { attempts }) = result else {
+                panic!("Expected generation to give up");
+            };
+            // Workers finishing their climbs at the same time can go slightly over.
+            assert!((2_000..3_000).contains(&attempts), "{attempts}");
+            if threads == 1 {
+                assert_eq!(attempts, 2_000);
+            }
+        }
+    }
+
+    #[test]
+    fn generation_gives_up_when_time_budget_runs_out() {
+        let config = GameConfig {
+            cols: 5,
+            rows: 5,
+            minimum_moves_required: 30,
+            time_budget: Some(Duration::from_millis(300)),
+            ..GameConfig::default()
+        };
+        let mut reports = Vec::new();
+
+        let result = Board::generate_solvable_board_with_progress(
+            &config,
+            None,
+            &AtomicBool::new(false),
+            |progress| reports.push(*progress),
+        );
+
+        assert!(matches!(
+            result,
+            Err(GlacierError::GenerationExhausted { .. })
+        ));
+        let last = reports.last().expect("progress should have been reported");
+        assert!(last.attempts > 0);
+        assert!(last.best_moves.is_some_and(|moves| moves < 30));
+        assert!(last.fraction() > 0.0 && last.fraction() <= 1.0);
+    }
+
+    #[test]
+    fn progress_fraction_uses_the_budget_closest_to_running_out() {
+        let progress = GenerationProgress {
+            attempts: 250,
+            max_attempts: 1000,
+            elapsed: Duration::from_secs(5),
+            time_budget: Some(Duration::from_secs(10)),
+            best_moves: None,
+        };
+        assert_eq!(progress.fraction(), 0.5);
+        assert_eq!(
+            GenerationProgress {
+                time_budget: None,
+                ..progress
+            }
+            .fraction(),
+            0.25
+        );
+    }
+
+    #[test]
+    fn generation_can_require_a_difficulty_score() {
+        let easy = GameConfig {
+            seed: Some(3),
+            ..GameConfig::get_config_from_difficulty("easy").unwrap()
+        };
+        let first = Board::generate_solvable_board(&easy, None).unwrap();
+        let first_score = first.layout.difficulty.as_ref().unwrap().score;
+
+        let harder = GameConfig {
+            min_difficulty_score: Some(first_score + 10),
+            ..easy
+        };
+        let board = Board::generate_solvable_board(&harder, None).unwrap();
+        let metrics = board.layout.difficulty.as_ref().unwrap();
+        assert!(metrics.score >= first_score + 10);
+        assert!(board.generation_attempts > first.generation_attempts);
+
+        let json: serde_json::Value = serde_json::from_str(&board.get_layout_json()).unwrap();
+        assert_eq!(json["difficulty"]["score"], metrics.score);
+    }
+
+    #[test]
+    fn generation_can_require_a_unique_solution() {
+        let medium = GameConfig {
+            seed: Some(1700),
+            ..GameConfig::get_config_from_difficulty("medium").unwrap()
+        };
+        // The first level found from this seed can be solved in two different ways.
+        let first = Board::generate_solvable_board(&medium, None).unwrap();
+        assert_eq!(first.count_optimal_solutions(), 2);
+
+        let unique = GameConfig {
+            unique_solution: true,
+            ..medium
+        };
+        let board = Board::generate_solvable_board(&unique, None).unwrap();
+        assert_eq!(board.count_optimal_solutions(), 1);
+        assert_eq!(
+            board.layout.difficulty.as_ref().unwrap().optimal_solutions,
+            1
+        );
+        assert!(board.generation_attempts > first.generation_attempts);
+    }
+
+    #[test]
+    fn generation_stays_within_the_accepted_moves() {
+        let easy = GameConfig::get_config_from_difficulty("easy").unwrap();
+        for seed in 0..10 {
+            let board = Board::generate_solvable_board(
+                &GameConfig {
+                    seed: Some(seed),
+                    ..easy.clone()
+                },
+                None,
+            )
+            .unwrap();
+            assert!((7..=9).contains(&board.par().unwrap()));
+
+            let exact = GameConfig {
+                seed: Some(seed),
+                maximum_moves: Some(7),
+                ..easy.clone()
+            };
+            let board = Board::generate_solvable_board(&exact, None).unwrap();
+            assert_eq!(board.par(), Some(7));
+        }
+    }
+}
@@ -0,0 +1,285 @@
+use std::ops::RangeInclusive;
+
+use rand::{seq::IndexedRandom, Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use super::{Board, Direction, Point};
+use crate::game_state::GameConfig;
+
+// Mutations tried in a single climb.
+const CLIMB_STEPS: u32 = 2_000;
+// A climb that has not improved for this many mutations has reached a peak.
+const CLIMB_PATIENCE: u32 = 300;
+
+// How hard a board is: the length of its shortest solution, then how many positions the solver
+// had to explore to find it. Unsolvable boards rank lowest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Fitness {
+    moves: Option<u32>,
+    explored: u32,
+}
+
+impl Fitness {
+    // Solutions longer than max_depth + 1 moves are not looked for, so those boards rank as
+    // unsolvable.
+    fn of(board: &Board, max_depth: u16) -> Self {
+        let solution = board.solve_with_max_depth(max_depth);
+        Fitness {
+            moves: solution.steps.map(|steps| steps.len() as u32),
+            explored: solution.edges_traversed,
+        }
+    }
+}
+
+// Rock counts the climb keeps to, so the result still looks like the requested rock density.
+fn rock_band(game_config: &GameConfig) -> (usize, usize) {
+    let interior = (game_config.cols as usize - 2) * (game_config.rows as usize - 2);
+    let expected = interior * game_config.rock_probability as usize / 100;
+    let spread = (expected / 4).max(1);
+    (
+        expected.saturating_sub(spread),
+        (expected + spread).min(interior),
+    )
+}
+
+struct Climb<'a> {
+    game_config: &'a GameConfig,
+    rows: usize,
+    cols: usize,
+    start: Point,
+    end: Point,
+    rocks: Vec<Point>,
+}
+
+impl Climb<'_> {
+    fn board(&self) -> Board {
+        self.board_with(self.rocks.clone())
+    }
+
+    fn board_with(&self, rocks: Vec<Point>) -> Board {
+        Board::new(self.rows, self.cols, self.start, self.end, rocks)
+    }
+
+    fn random_free_tile(&self, rng: &mut impl Rng) -> Option<Point> {
+        // Boards are mostly ice, so a few guesses almost always find a free tile.
+        (0..20)
+            .map(|_| Point {
+                col: rng.random_range(1..self.cols - 1),
+                row: rng.random_range(1..self.rows - 1),
+            })
+            .find(|p| !self.rocks.contains(p))
+    }
+
+    // Add, remove or shift a single rock, staying inside the rock band. None if the chosen change
+    // was not possible.
+    fn mutate(&self, rng: &mut impl Rng) -> Option<Vec<Point>> {
+        let (fewest, most) = rock_band(self.game_config);
+        let mut rocks = self.rocks.clone();
+
+        match rng.random_range(0..3) {
+            0 if rocks.len() < most => rocks.push(self.random_free_tile(rng)?),
+            1 if rocks.len() > fewest => {
+                rocks.swap_remove(rng.random_range(0..rocks.len()));
+            }
+            _ => {
+                let index = rng.random_range(0..rocks.len().max(1));
+                let rock = *rocks.get(index)?;
+                let moved = match Direction::ALL.choose(rng)? {
+                    Direction::Up => Point {
+                        row: rock.row - 1,
+                        ..rock
+                    },
+                    Direction::Down => Point {
+                        row: rock.row + 1,
+                        ..rock
+                    },
+                    Direction::Left => Point {
+                        col: rock.col - 1,
+                        ..rock
+                    },
+                    Direction::Right => Point {
+                        col: rock.col + 1,
+                        ..rock
+                    },
+                };
+                let interior = (1..self.cols - 1).contains(&moved.col)
+                    && (1..self.rows - 1).contains(&moved.row);
+                if !interior || rocks.contains(&moved) {
+                    return None;
+                }
+                rocks[index] = moved;
+            }
+        }
+
+        Some(rocks)
+    }
+}
+
+impl Board {
+    // Start from the random board for `seed` and keep changing one rock at a time, keeping every
+    // change that does not make the board easier. With a target range of moves, the climb stops as
+    // soon as the shortest solution falls in it, and changes that overshoot it are thrown away.
+    //
+    // The climb also stops after solving `max_boards` boards, or as soon as `stop` returns true.
+    // Returns the board it ended on and the number of boards it solved.
+    fn hill_climb(
+        game_config: &GameConfig,
+        seed: u64,
+        target: Option<RangeInclusive<u16>>,
+        max_boards: u32,
+        stop: &dyn Fn() -> bool,
+    ) -> (Board, u32) {
+        let board = Board::generate_random_board(game_config, seed);
+        let mut climb = Climb {
+            game_config,
+            rows: board.layout.rows,
+            cols: board.layout.cols,
+            start: board.layout.start.0,
+            end: board.layout.end.0,
+            rocks: board.layout.rocks.iter().map(|r| r.0).collect(),
+        };
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        // The mutations use their own stream, so they do not repeat the numbers the board was
+        // generated from.
+        rng.set_stream(1);
+
+        // A random board can land outside the rock band, so bring it inside before climbing.
+        let (fewest, most) = rock_band(game_config);
+        while climb.rocks.len() > most {
+            climb
+                .rocks
+                .swap_remove(rng.random_range(0..climb.rocks.len()));
+        }
+        while climb.rocks.len() < fewest {
+            match climb.random_free_tile(&mut rng) {
+                Some(tile) => climb.rocks.push(tile),
+                None => break,
+            }
+        }
+
+        let reached_target = |fitness: &Fitness| {
+            target
+                .as_ref()
+                .is_some_and(|t| fitness.moves.is_some_and(|m| m >= u32::from(*t.start())))
+        };
+        let overshot = |fitness: &Fitness| {
+            target
+                .as_ref()
+                .is_some_and(|t| fitness.moves.is_some_and(|m| m > u32::from(*t.end())))
+        };
+
+        // Only boards that overshoot by a single move need to be told apart from unsolvable ones.
+        let max_depth = target.as_ref().map_or(u16::MAX, |t| *t.end());
+        let mut fitness = Fitness::of(&climb.board(), max_depth);
+        let mut boards_solved = 1;
+        let mut since_improvement = 0;
+        for _ in 0..CLIMB_STEPS {
+            if reached_target(&fitness)
+                || since_improvement >= CLIMB_PATIENCE
+                || boards_solved >= max_boards
+                || stop()
+            {
+                break;
+            }
+            since_improvement += 1;
+
+            let Some(rocks) = climb.mutate(&mut rng) else {
+                continue;
+            };
+            let candidate = Fitness::of(&climb.board_with(rocks.clone()), max_depth);
+            boards_solved += 1;
+            if candidate >= fitness && !overshot(&candidate) {
+                if candidate > fitness {
+                    since_improvement = 0;
+                }
+                fitness = candidate;
+                climb.rocks = rocks;
+            }
+        }
+
+        let mut board = climb.board();
+        board.layout.seed = Some(seed);
+        (board, boards_solved)
+    }
+
+    // Climb as far as possible from the random board for `seed`, ignoring the minimum number of
+    // moves. The result is as hard as a single climb can make a board of this size and rock
+    // density, and comes with its solution. Library only; the CLI and server always have a target.
+    pub fn generate_hardest_board(game_config: &GameConfig, seed: u64) -> Board {
+        let (mut board, _) = Board::hill_climb(game_config, seed, None, u32::MAX, &|| false);
+        board.solution = Some(board.solve());
+        board
+    }
+
+    // A climb aiming for the range of moves the generator accepts.
+    pub(super) fn generate_climbed_board(
+        game_config: &GameConfig,
+        seed: u64,
+        max_boards: u32,
+        stop: &dyn Fn() -> bool,
+    ) -> (Board, u32) {
+        let target = Some(game_config.accepted_moves());
+        Board::hill_climb(game_config, seed, target, max_boards, stop)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GenerationStrategy;
+
+    #[test]
+    fn climbing_makes_boards_harder() {
+        let config = GameConfig::get_config_from_difficulty("medium").unwrap();
+        let (fewest, most) = rock_band(&config);
+        let (mut random_moves, mut climbed_moves) = (0, 0);
+
+        for seed in 0..5 {
+            let random = Board::generate_random_board(&config, seed);
+            let hardest = Board::generate_hardest_board(&config, seed);
+            random_moves += Fitness::of(&random, u16::MAX).moves.unwrap_or(0);
+            climbed_moves += hardest.par().unwrap_or(0);
+
+            // The start, end and seed are kept, so the climb can be repeated.
+            assert_eq!(hardest.layout.start.0, random.layout.start.0);
+            assert_eq!(hardest.layout.end.0, random.layout.end.0);
+            assert_eq!(hardest.seed(), Some(seed));
+            assert!((fewest..=most).contains(&hardest.layout.rocks.len()));
+        }
+
+        assert!(climbed_moves > random_moves);
+    }
+
+    #[test]
+    fn climb_stops_when_told_to_or_out_of_boards() {
+        let config = GameConfig::get_config_from_difficulty("hard").unwrap();
+
+        let (_, solved) = Board::generate_climbed_board(&config, 1, u32::MAX, &|| true);
+        assert_eq!(solved, 1);
+
+        let (_, solved) = Board::generate_climbed_board(&config, 1, 10, &|| false);
+        assert!(solved <= 10);
+    }
+
+    #[test]
+    fn hill_climb_generation_needs_far_fewer_attempts() {
+        let config = GameConfig {
+            cols: 9,
+            rows: 9,
+            minimum_moves_required: 14,
+            rock_probability: 10,
+            seed: Some(1),
+            strategy: GenerationStrategy::HillClimb,
+            board_only: true,
+            ..GameConfig::default()
+        };
+
+        let board = Board::generate_solvable_board(&config, None).unwrap();
+        assert!(board.par().unwrap() >= 14);
+        assert!(board.generation_attempts < 100);
+
+        // The same seed and config climb to the same level.
+        let again = Board::generate_solvable_board(&config, None).unwrap();
+        assert_eq!(board.get_layout_json(), again.get_layout_json());
+    }
+}
@@ -0,0 +1,103 @@
+use super::{Board, Direction, Point};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hint {
+    // The first slide of a shortest path from the player's resting position.
+    Slide(Direction),
+    // The end cannot be reached from here, so only undo or reset helps.
+    DeadEnd,
+}
+
+impl Board {
+    pub fn hint(&self) -> Option<Hint> {
+        self.hint
+    }
+
+    pub fn hints_used(&self) -> u32 {
+        self.hints_used
+    }
+
+    // Work out the next optimal slide from where the player is resting. Does nothing while the
+    // player is still moving, or if a hint is already showing.
+    pub fn show_hint(&mut self) -> Option<Hint> {
+        if self.player_has_won || !self.move_queue.is_empty() || self.hint.is_some() {
+            return self.hint;
+        }
+
+        let solution = self.shortest_path_from(self.player.0);
+        let hint = match solution.steps.as_ref().and_then(|steps| steps.first()) {
+            Some(direction) => {
+                self.hints_used += 1;
+                Hint::Slide(*direction)
+            }
+            None => Hint::DeadEnd,
+        };
+
+        self.hint = Some(hint);
+        self.hint
+    }
+
+    // The hint arrow is drawn on the tile the player would slide into first. Filled arrows are
+    // used, so hints stand out from the hollow start and end markers.
+    pub(super) fn hint_arrow_at(&self, tile: Point) -> Option<&'static str> {
+        let Some(Hint::Slide(direction)) = self.hint else {
+            return None;
+        };
+
+        let p = self.player.0;
+        let (next, arrow) = match direction {
+            Direction::Up => ((p.col, p.row - 1), "▲ "),
+            Direction::Down => ((p.col, p.row + 1), "▼ "),
+            Direction::Left => ((p.col - 1, p.row), "◀ "),
+            Direction::Right => ((p.col + 1, p.row), "▶ "),
+        };
+        (next == (tile.col, tile.row)).then_some(arrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::{board_with_dead_end, simple_board};
+
+    fn finish_moves(board: &mut Board) {
+        while board.process_move().is_some() {}
+    }
+
+    #[test]
+    fn hint_uses_players_current_position() {
+        let mut board = simple_board();
+
+        assert_eq!(board.show_hint(), Some(Hint::Slide(Direction::Right)));
+        assert_eq!(board.hint_arrow_at(Point { col: 1, row: 1 }), Some("▶ "));
+        assert_eq!(board.hint_arrow_at(Point { col: 2, row: 1 }), None);
+        // Asking again while the hint is showing is free.
+        board.show_hint();
+        assert_eq!(board.hints_used(), 1);
+        // Solving for the hint must not move the player.
+        assert_eq!(board.player.0, Point { col: 0, row: 1 });
+
+        board.queue_slide(Direction::Right);
+        finish_moves(&mut board);
+        assert_eq!(board.hint(), None);
+
+        assert_eq!(board.show_hint(), Some(Hint::Slide(Direction::Down)));
+        assert_eq!(board.hints_used(), 2);
+    }
+
+    #[test]
+    fn hint_reports_dead_end() {
+        let mut board = board_with_dead_end();
+
+        // From the top right corner, the player can never come to rest in the end's column again.
+        for direction in [Direction::Left, Direction::Up, Direction::Right] {
+            board.queue_slide(direction);
+            finish_moves(&mut board);
+        }
+        assert_eq!(board.player.0, Point { col: 4, row: 1 });
+
+        assert_eq!(board.show_hint(), Some(Hint::DeadEnd));
+        assert_eq!(board.hints_used(), 0);
+        assert_eq!(board.hint_arrow_at(Point { col: 4, row: 2 }), None);
+    }
+}
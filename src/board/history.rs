@@ -0,0 +1,159 @@
+use super::{Board, Direction, Move, Point, Slide};
+
+// Resting positions the player can return to with undo, or move forward to again with redo.
+#[derive(Debug, Default)]
+pub struct History {
+    undo: Vec<Point>,
+    redo: Vec<Point>,
+    undo_count: u32,
+    // Every slide the player chose on this level, including ones later undone or reset.
+    slides: u32,
+}
+
+impl History {
+    // A new slide starts from `from`, which drops anything that could have been redone.
+    pub(super) fn record(&mut self, from: Point) {
+        self.undo.push(from);
+        self.redo.clear();
+        self.slides += 1;
+    }
+
+    pub(super) fn slides(&self) -> u32 {
+        self.slides
+    }
+
+    // Forget every position, but keep counting the slides and undos used on this level.
+    pub(super) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+// The slide that moves the player in a straight line from `from` to `to`.
+fn slide_between(from: Point, to: Point) -> Option<Slide> {
+    let (steps, direction) = if from.row == to.row && from.col < to.col {
+        (to.col - from.col, Direction::Right)
+    } else if from.row == to.row && from.col > to.col {
+        (from.col - to.col, Direction::Left)
+    } else if from.col == to.col && from.row < to.row {
+        (to.row - from.row, Direction::Down)
+    } else if from.col == to.col && from.row > to.row {
+        (from.row - to.row, Direction::Up)
+    } else {
+        return None;
+    };
+
+    Some(Slide::new(steps, direction))
+}
+
+impl Board {
+    pub fn undo_count(&self) -> u32 {
+        self.history.undo_count
+    }
+
+    // Slide back to the previous resting position. Does nothing while the player is still moving.
+    pub fn queue_undo(&mut self) {
+        if self.player_has_won || !self.move_queue.is_empty() {
+            return;
+        }
+
+        if let Some(target) = self.history.undo.pop() {
+            if let Some(slide) = slide_between(self.player.0, target) {
+                self.history.redo.push(self.player.0);
+                self.history.undo_count += 1;
+                self.move_queue.push_back(Move::SlidePlayer(slide));
+            }
+        }
+    }
+
+    // Slide forward again to the position the last undo left.
+    pub fn queue_redo(&mut self) {
+        if self.player_has_won || !self.move_queue.is_empty() {
+            return;
+        }
+
+        if let Some(target) = self.history.redo.pop() {
+            if let Some(slide) = slide_between(self.player.0, target) {
+                self.history.undo.push(self.player.0);
+                self.move_queue.push_back(Move::SlidePlayer(slide));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::simple_board;
+
+    fn finish_moves(board: &mut Board) {
+        while board.process_move().is_some() {}
+    }
+
+    #[test]
+    fn slide_between_finds_direction_and_steps() {
+        let from = Point { col: 3, row: 1 };
+
+        let slide = slide_between(from, Point { col: 0, row: 1 }).unwrap();
+        assert_eq!((slide.steps, slide.direction), (3, Direction::Left));
+
+        let slide = slide_between(from, Point { col: 3, row: 3 }).unwrap();
+        assert_eq!((slide.steps, slide.direction), (2, Direction::Down));
+
+        assert!(slide_between(from, Point { col: 1, row: 3 }).is_none());
+    }
+
+    #[test]
+    fn undo_returns_to_previous_position_and_redo_moves_forward_again() {
+        let mut board = simple_board();
+        board.queue_slide(Direction::Right);
+        finish_moves(&mut board);
+        assert_eq!(board.player.0, Point { col: 3, row: 1 });
+
+        board.queue_undo();
+        finish_moves(&mut board);
+        assert_eq!(board.player.0, Point { col: 0, row: 1 });
+        assert_eq!(board.undo_count(), 1);
+
+        board.queue_redo();
+        finish_moves(&mut board);
+        assert_eq!(board.player.0, Point { col: 3, row: 1 });
+
+        // Nothing is left to redo.
+        board.queue_redo();
+        assert!(board.move_queue.is_empty());
+    }
+
+    #[test]
+    fn new_slide_clears_redo() {
+        let mut board = simple_board();
+        board.queue_slide(Direction::Right);
+        finish_moves(&mut board);
+        board.queue_undo();
+        finish_moves(&mut board);
+
+        // Right again from the start is a new move, not a redo.
+        board.queue_slide(Direction::Right);
+        finish_moves(&mut board);
+        board.queue_redo();
+        assert!(board.move_queue.is_empty());
+    }
+
+    #[test]
+    fn reset_clears_history_but_keeps_undo_count() {
+        let mut board = simple_board();
+        board.queue_slide(Direction::Right);
+        finish_moves(&mut board);
+        board.queue_undo();
+        finish_moves(&mut board);
+        board.queue_redo();
+        finish_moves(&mut board);
+
+        board.queue_reset();
+        finish_moves(&mut board);
+        board.queue_undo();
+
+        assert!(board.move_queue.is_empty());
+        assert_eq!(board.undo_count(), 1);
+    }
+}
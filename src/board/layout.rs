@@ -0,0 +1,392 @@
+use std::collections::HashSet;
+
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::analysis::DifficultyMetrics;
+use super::point::Point;
+use super::tile::{End, Rock, Start, Tile};
+use crate::error::GlacierError;
+use crate::game_state::DIMENSION_BOUNDS;
+
+fn grid_as_strings<S>(grid: &[Vec<Tile>], serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    let rows: Vec<String> = grid
+        .iter()
+        .map(|row| row.iter().map(Tile::as_char).collect())
+        .collect();
+
+    rows.serialize(serializer)
+}
+
+fn grid_from_strings<'de, D>(deserializer: D) -> Result<Vec<Vec<Tile>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let rows = Vec::<String>::deserialize(deserializer)?;
+
+    rows.iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| {
+                    Tile::from_char(c)
+                        .ok_or_else(|| de::Error::custom(format!("unknown tile '{}' in grid", c)))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// Seeds are written as strings, because JavaScript numbers lose precision above 2^53 and the
+// level could not be reproduced. Seeds written as numbers are still read.
+fn seed_as_string<S>(seed: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    seed.map(|s| s.to_string()).serialize(serializer)
+}
+
+fn seed_from_string_or_number<'de, D>(deserializer: D) -> Result<Option<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seed {
+        Number(u64),
+        Text(String),
+    }
+
+    match Option::<Seed>::deserialize(deserializer)? {
+        None => Ok(None),
+        Some(Seed::Number(seed)) => Ok(Some(seed)),
+        Some(Seed::Text(text)) => text
+            .parse()
+            .map(Some)
+            .map_err(|_| de::Error::custom(format!("invalid seed '{}'", text))),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BoardLayout {
+    pub(super) rows: usize, // Value includes the left and right border columns
+    pub(super) cols: usize, // Value includes the top and bottom border rows
+    pub(super) start: Start,
+    pub(super) end: End,
+    pub(super) rocks: Vec<Rock>,
+    // The seed the level was generated from (absent for boards that were not randomly generated).
+    // Generating with the same seed and config reproduces the level exactly.
+    #[serde(
+        default,
+        serialize_with = "seed_as_string",
+        deserialize_with = "seed_from_string_or_number"
+    )]
+    pub(super) seed: Option<u64>,
+    // How hard generated levels are (absent for boards that were not generated).
+    #[serde(default)]
+    pub(super) difficulty: Option<DifficultyMetrics>,
+    // Including the grid is redundant (it can be derived from other fields),
+    // but it provides a clean, human-readable layout of the level.
+    #[serde(
+        serialize_with = "grid_as_strings",
+        deserialize_with = "grid_from_strings"
+    )]
+    pub(super) grid: Vec<Vec<Tile>>,
+}
+
+impl BoardLayout {
+    // Build a layout from the rows of an ASCII grid, deriving the start, end and rocks from it.
+    pub fn from_grid_lines(lines: &[&str]) -> Result<Self, GlacierError> {
+        BoardLayout::parse_grid_lines(lines).map_err(GlacierError::InvalidBoard)
+    }
+
+    fn parse_grid_lines(lines: &[&str]) -> Result<Self, String> {
+        let mut grid = Vec::with_capacity(lines.len());
+        let mut start = None;
+        let mut end = None;
+        let mut rocks = Vec::new();
+
+        for (row, line) in lines.iter().enumerate() {
+            let mut tiles = Vec::with_capacity(line.len());
+            for (col, c) in line.chars().enumerate() {
+                let tile = Tile::from_char(c).ok_or_else(|| {
+                    format!("Unknown tile '{}' at [{}, {}] in grid.", c, col, row)
+                })?;
+                let p = Point { col, row };
+                let duplicate = match tile {
+                    Tile::Start | Tile::Player => start.replace(p).is_some(),
+                    Tile::End => end.replace(p).is_some(),
+                    Tile::Rock => {
+                        rocks.push(Rock(p));
+                        false
+                    }
+                    Tile::Wall | Tile::Ice => false,
+                };
+                if duplicate {
+                    return Err(format!("The grid has more than one '{}' tile.", c));
+                }
+                tiles.push(tile);
+            }
+            grid.push(tiles);
+        }
+
+        Ok(BoardLayout {
+            rows: grid.len(),
+            cols: grid.first().map_or(0, Vec::len),
+            start: Start(start.ok_or("The grid has no start ('S') tile.")?),
+            end: End(end.ok_or("The grid has no end ('E') tile.")?),
+            rocks,
+            seed: None,
+            difficulty: None,
+            grid,
+        })
+    }
+
+    fn is_on_border(&self, p: Point) -> bool {
+        p.row == 0 || p.col == 0 || p.row == self.rows - 1 || p.col == self.cols - 1
+    }
+
+    fn is_corner(&self, p: Point) -> bool {
+        (p.row == 0 || p.row == self.rows - 1) && (p.col == 0 || p.col == self.cols - 1)
+    }
+
+    fn is_inside(&self, p: Point) -> bool {
+        p.row < self.rows && p.col < self.cols
+    }
+
+    fn validate_border_point(&self, name: &str, p: Point) -> Result<(), String> {
+        if !self.is_inside(p) {
+            Err(format!(
+                "The {} [{}, {}] is outside the board.",
+                name, p.col, p.row
+            ))
+        } else if !self.is_on_border(p) || self.is_corner(p) {
+            Err(format!(
+                "The {} [{}, {}] must be on the border (but not in a corner).",
+                name, p.col, p.row
+            ))
+        } else {
+            Ok(())
+        }
+    }
+
+    // Check that the layout describes a playable level, and that the grid agrees with the
+    // start, end and rock fields.
+    pub fn validate(&self) -> Result<(), GlacierError> {
+        self.check().map_err(GlacierError::InvalidBoard)
+    }
+
+    fn check(&self) -> Result<(), String> {
+        if self.rows < 3 || self.cols < 3 {
+            return Err(format!(
+                "The board must be at least 3x3 (including borders), but is {}x{}.",
+                self.cols, self.rows
+            ));
+        }
+        // Generated levels are never larger than this, and the solver does not need to handle more.
+        let max_size = usize::from(*DIMENSION_BOUNDS.end()) + 2;
+        if self.rows > max_size || self.cols > max_size {
+            return Err(format!(
+                "The board must be at most {}x{} (including borders), but is {}x{}.",
+                max_size, max_size, self.cols, self.rows
+            ));
+        }
+
+        let start = self.start.0;
+        let end = self.end.0;
+        self.validate_border_point("start", start)?;
+        self.validate_border_point("end", end)?;
+        if start == end {
+            return Err("The start and end must be different tiles.".to_string());
+        }
+
+        let mut rocks = HashSet::new();
+        for rock in &self.rocks {
+            let p = rock.0;
+            if !self.is_inside(p) || self.is_on_border(p) {
+                return Err(format!(
+                    "The rock [{}, {}] must be inside the border.",
+                    p.col, p.row
+                ));
+            }
+            if !rocks.insert(p) {
+                return Err(format!("The rock [{}, {}] is listed twice.", p.col, p.row));
+            }
+        }
+
+        if self.grid.len() != self.rows {
+            return Err(format!(
+                "The grid has {} rows, but the board has {}.",
+                self.grid.len(),
+                self.rows
+            ));
+        }
+
+        for (row, tiles) in self.grid.iter().enumerate() {
+            if tiles.len() != self.cols {
+                return Err(format!(
+                    "Grid row {} has {} columns, but the board has {}.",
+                    row,
+                    tiles.len(),
+                    self.cols
+                ));
+            }
+
+            for (col, tile) in tiles.iter().enumerate() {
+                let p = Point { col, row };
+                let matches = if p == start {
+                    // The player may be shown standing on the start.
+                    matches!(tile, Tile::Start | Tile::Player)
+                } else if p == end {
+                    matches!(tile, Tile::End)
+                } else if self.is_on_border(p) {
+                    matches!(tile, Tile::Wall)
+                } else if rocks.contains(&p) {
+                    matches!(tile, Tile::Rock)
+                } else {
+                    matches!(tile, Tile::Ice)
+                };
+
+                if !matches {
+                    return Err(format!(
+                        "Grid tile '{}' at [{}, {}] does not match the start, end and rocks.",
+                        tile.as_char(),
+                        col,
+                        row
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> BoardLayout {
+        serde_json::from_str(json).unwrap()
+    }
+
+    /*
+    WWWWW
+    S   W
+    W R W
+    W   W
+    WWWEW
+    */
+    const VALID: &str = r#"{
+        "rows": 5, "cols": 5, "start": [0, 1], "end": [3, 4], "rocks": [[2, 2]],
+        "grid": ["WWWWW", "S   W", "W R W", "W   W", "WWWEW"]
+    }"#;
+
+    #[test]
+    fn valid_layout_passes_validation() {
+        let layout = parse(VALID);
+        assert!(layout.validate().is_ok());
+        assert_eq!(layout.seed, None);
+    }
+
+    #[test]
+    fn layout_round_trips_through_json() {
+        let layout = parse(VALID);
+        let json = serde_json::to_string(&layout).unwrap();
+        let reparsed = parse(&json);
+
+        assert_eq!(reparsed.start.0, layout.start.0);
+        assert_eq!(reparsed.end.0, layout.end.0);
+        assert!(reparsed.validate().is_ok());
+    }
+
+    #[test]
+    fn seed_is_written_as_a_string_and_read_either_way() {
+        let mut layout = parse(VALID);
+        layout.seed = Some(u64::MAX);
+        let json: serde_json::Value = serde_json::to_value(&layout).unwrap();
+        assert_eq!(json["seed"], "18446744073709551615");
+        assert_eq!(parse(&json.to_string()).seed, Some(u64::MAX));
+
+        let with_number = VALID.replacen('{', r#"{ "seed": 42,"#, 1);
+        assert_eq!(parse(&with_number).seed, Some(42));
+
+        let with_bad_seed = VALID.replacen('{', r#"{ "seed": "abc","#, 1);
+        assert!(serde_json::from_str::<BoardLayout>(&with_bad_seed).is_err());
+    }
+
+    #[test]
+    fn from_grid_lines_matches_json_layout() {
+        let lines = ["WWWWW", "S   W", "W R W", "W   W", "WWWEW"];
+        let layout = BoardLayout::from_grid_lines(&lines).unwrap();
+
+        assert!(layout.validate().is_ok());
+        assert_eq!(
+            serde_json::to_value(&layout).unwrap(),
+            serde_json::to_value(parse(VALID)).unwrap()
+        );
+    }
+
+    #[test]
+    fn from_grid_lines_requires_start_and_end() {
+        let err = BoardLayout::from_grid_lines(&["WWWWW", "W   W", "WWWEW"])
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(err.contains("start"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn unknown_grid_tile_fails_to_deserialize() {
+        let json = VALID.replace("W R W", "W X W");
+        assert!(serde_json::from_str::<BoardLayout>(&json).is_err());
+    }
+
+    #[test]
+    fn start_in_corner_is_rejected() {
+        let json = VALID
+            .replace(r#""start": [0, 1]"#, r#""start": [0, 0]"#)
+            .replace("\"S   W\"", "\"W   W\"")
+            .replace("\"WWWWW\", \"W   W\"", "\"SWWWW\", \"W   W\"");
+        let err = parse(&json).validate().unwrap_err().to_string();
+        assert!(err.contains("start"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn end_inside_the_border_is_rejected() {
+        let json = VALID.replace(r#""end": [3, 4]"#, r#""end": [3, 3]"#);
+        let err = parse(&json).validate().unwrap_err().to_string();
+        assert!(err.contains("end"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn rock_missing_from_grid_is_rejected() {
+        let json = VALID.replace("W R W", "W   W");
+        let err = parse(&json).validate().unwrap_err().to_string();
+        assert!(err.contains("[2, 2]"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn grid_with_wrong_dimensions_is_rejected() {
+        let json = VALID.replace("\"W   W\", \"WWWEW\"", "\"WWWEW\"");
+        let err = parse(&json).validate().unwrap_err().to_string();
+        assert!(err.contains("rows"), "unexpected error: {err}");
+    }
+
+    #[test]
+    fn oversized_grid_is_rejected() {
+        // A valid 300x3 level, far wider than any level the game generates.
+        let top = format!("WS{}", "W".repeat(298));
+        let middle = format!("W{}W", " ".repeat(298));
+        let bottom = format!("{}EW", "W".repeat(298));
+        let layout = BoardLayout::from_grid_lines(&[&top, &middle, &bottom]).unwrap();
+
+        let err = layout.validate().unwrap_err();
+        assert!(matches!(err, GlacierError::InvalidBoard(_)));
+        assert_eq!(
+            err.to_string(),
+            "The board must be at most 22x22 (including borders), but is 300x3."
+        );
+    }
+}
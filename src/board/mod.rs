@@ -1,60 +1,62 @@
+pub mod analysis;
+mod backward;
 pub mod direction;
+mod generator;
+mod hill_climb;
+pub mod hint;
+mod history;
+pub mod layout;
 pub mod point;
+pub mod replay;
+pub mod score;
+mod search;
+mod slide_table;
 pub mod solution;
+#[cfg(test)]
+mod test_boards;
 pub mod tile;
 
+pub use analysis::{BoardAnalysis, DifficultyMetrics, Reachability};
 pub use direction::{Direction, Move, Slide};
+pub use generator::GenerationProgress;
+pub use hint::Hint;
+pub use layout::BoardLayout;
 pub use point::Point;
+pub use replay::Replay;
+pub use score::LevelScore;
 pub use solution::Solution;
 pub use tile::{End, Player, Rock, Start, Tile};
 
+use history::History;
+
+use crate::error::GlacierError;
 use crate::game_state::GameConfig;
-use crate::system::exit_game;
 
+#[cfg(feature = "tui")]
 use crossterm::event::KeyCode;
-use rand::Rng;
-use serde::{Serialize, Serializer};
-use thousands::Separable;
-use time_elapsed::{self, TimeElapsed};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
 use std::{
-    collections::{HashSet, VecDeque},
+    collections::VecDeque,
+    fs,
     hash::{DefaultHasher, Hash, Hasher},
+    path::Path,
+    sync::atomic::AtomicBool,
 };
 
-fn grid_as_strings<S>(grid: &[Vec<Tile>], serializer: S) -> Result<S::Ok, S::Error>
-where
-    S: Serializer,
-{
-    let rows: Vec<String> = grid
-        .iter()
-        .map(|row| row.iter().map(Tile::as_char).collect())
-        .collect();
-
-    rows.serialize(serializer)
-}
-
-#[derive(Serialize)]
-pub struct BoardLayout {
-    rows: usize, // Value includes the left and right border columns
-    cols: usize, // Value includes the top and bottom border rows
-    start: Start,
-    end: End,
-    rocks: Vec<Rock>,
-    // Including the grid is redundant (it can be derived from other fields),
-    // but it provides a clean, human-readable layout of the level.
-    #[serde(serialize_with = "grid_as_strings")]
-    grid: Vec<Vec<Tile>>,
-}
-
 // Board coordinates start at 0, 0 in the top left corner
 pub struct Board {
     layout: BoardLayout,
     pub player: Player,
     pub move_queue: VecDeque<Move>,
     pub player_has_won: bool,
-    bot_is_solving: bool,
     pub solution: Option<Solution>,
+    // Number of random boards tried before this one was accepted (0 if it was not generated).
+    pub generation_attempts: u32,
+    history: History,
+    hint: Option<Hint>,
+    hints_used: u32,
 }
 
 impl Board {
@@ -88,21 +90,91 @@ impl Board {
                 start: Start(start),
                 end: End(end),
                 rocks: rocks.iter().copied().map(Rock).collect(),
+                seed: None,
+                difficulty: None,
                 grid,
             },
             player: Player(start),
             move_queue: VecDeque::new(),
             player_has_won: false,
-            bot_is_solving: false,
             solution: None,
+            generation_attempts: 0,
+            history: History::default(),
+            hint: None,
+            hints_used: 0,
         }
     }
 
+    // Build a playable board from a layout, e.g. one read back from `generate` output.
+    pub fn from_layout(layout: BoardLayout) -> Result<Self, GlacierError> {
+        layout.validate()?;
+
+        let rocks = layout.rocks.iter().map(|r| r.0).collect();
+        let mut board = Board::new(
+            layout.rows,
+            layout.cols,
+            layout.start.0,
+            layout.end.0,
+            rocks,
+        );
+        board.layout.seed = layout.seed;
+
+        Ok(board)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, GlacierError> {
+        let layout: BoardLayout = serde_json::from_str(json)
+            .map_err(|e| GlacierError::InvalidBoard(format!("Invalid board JSON: {}", e)))?;
+        Board::from_layout(layout)
+    }
+
+    // Parse an ASCII grid using the same characters as the layout's "grid" field, e.g.
+    //   WWWWW
+    //   S   W
+    //   W R W
+    //   W   W
+    //   WWWEW
+    pub fn from_ascii(text: &str) -> Result<Self, GlacierError> {
+        let lines: Vec<&str> = text.lines().map(|l| l.trim_end_matches('\r')).collect();
+
+        // Ignore blank lines around the grid.
+        let first = lines.iter().position(|l| !l.is_empty());
+        let last = lines.iter().rposition(|l| !l.is_empty());
+        match (first, last) {
+            (Some(first), Some(last)) => {
+                Board::from_layout(BoardLayout::from_grid_lines(&lines[first..=last])?)
+            }
+            _ => Err(GlacierError::InvalidBoard(
+                "The board is empty.".to_string(),
+            )),
+        }
+    }
+
+    // Accept either a layout JSON object or an ASCII grid.
+    pub fn from_text(text: &str) -> Result<Self, GlacierError> {
+        if text.trim_start().starts_with('{') {
+            Board::from_json(text)
+        } else {
+            Board::from_ascii(text)
+        }
+    }
+
+    pub fn from_json_file(path: &Path) -> Result<Self, GlacierError> {
+        let json = fs::read_to_string(path).map_err(|e| {
+            GlacierError::io(format!("Could not read board file '{}'", path.display()), e)
+        })?;
+        Board::from_json(&json)
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.layout.seed
+    }
+
     pub fn get_layout_json(&self) -> String {
         serde_json::to_string_pretty(&self.layout).expect("Failed to serialize to JSON")
     }
 
-    fn get_random_start_and_end(cols: usize, rows: usize) -> (Point, Point) {
+    fn get_random_start_and_end(cols: usize, rows: usize, rng: &mut impl Rng) -> (Point, Point) {
         assert!(cols >= 3 && rows >= 3);
 
         let total_possible = (2 * (cols - 2)) + (2 * (rows - 2));
@@ -122,8 +194,6 @@ impl Board {
             possible_values.push(Point { col: cols - 1, row });
         }
 
-        let mut rng = rand::rng();
-
         let start_idx = rng.random_range(0..total_possible);
         let mut end_idx = rng.random_range(0..total_possible);
         while start_idx == end_idx {
@@ -133,8 +203,12 @@ impl Board {
         (possible_values[start_idx], possible_values[end_idx])
     }
 
-    fn generate_rock(col: usize, row: usize, percent_probability: u8) -> Option<Point> {
-        let mut rng = rand::rng();
+    fn generate_rock(
+        col: usize,
+        row: usize,
+        percent_probability: u8,
+        rng: &mut impl Rng,
+    ) -> Option<Point> {
         let value = rng.random_range(1..=100);
 
         if value <= percent_probability {
@@ -144,98 +218,53 @@ impl Board {
         }
     }
 
-    fn generate_random_board(game_config: &GameConfig) -> Self {
+    // The same seed and config always produce the same board.
+    pub fn generate_random_board(game_config: &GameConfig, seed: u64) -> Self {
         assert!(game_config.cols >= 3 && game_config.rows >= 3);
 
+        // ChaCha is used (rather than StdRng) because its output is stable across rand releases,
+        // so published seeds keep producing the same levels.
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
         let cols = game_config.cols as usize;
         let rows = game_config.rows as usize;
 
-        let (start, end) = Board::get_random_start_and_end(cols, rows);
+        let (start, end) = Board::get_random_start_and_end(cols, rows, &mut rng);
 
         let mut rocks = Vec::new();
         for col in 1..cols - 1 {
             for row in 1..rows - 1 {
-                if let Some(r) = Board::generate_rock(col, row, game_config.rock_probability) {
+                if let Some(r) =
+                    Board::generate_rock(col, row, game_config.rock_probability, &mut rng)
+                {
                     rocks.push(r);
                 }
             }
         }
 
-        Board::new(rows, cols, start, end, rocks)
+        let mut board = Board::new(rows, cols, start, end, rocks);
+        board.layout.seed = Some(seed);
+        board
     }
 
     pub fn generate_solvable_board(
         game_config: &GameConfig,
         request_id_opt: Option<u64>,
-    ) -> Result<Self, String> {
-        let mut time: Option<TimeElapsed> = None;
-        let mut board_count: u32 = 1;
-        let mut denominator: u32 = 1;
-        let request_id = request_id_opt.unwrap_or(1);
-
-        if game_config.debug {
-            time = Some(time_elapsed::start("level generator"));
-        } else if !game_config.board_only {
-            tracing::info!(request_id, "Generating level {:?}.", &game_config);
-        }
-
-        let mut board;
-
-        loop {
-            if board_count > 1_000_000 {
-                let msg = "Could not find solvable level after 1,000,000 attempts.";
-
-                if game_config.board_only {
-                    return Err(msg.to_string());
-                } else {
-                    tracing::error!(request_id, "{}", msg);
-                    exit_game();
-                }
-            }
-
-            board = Board::generate_random_board(game_config);
-
-            if game_config.debug && board_count % denominator == 0 {
-                denominator *= 10;
-
-                if let Some(t) = time.as_mut() {
-                    t.log_overall(format!(
-                        "Boards generated: {:9}",
-                        board_count.separate_with_commas()
-                    ));
-                }
-            }
-
-            board_count += 1;
-
-            let max_depth = game_config.minimum_moves_required + 2;
-            board.solve(max_depth);
-
-            let solution_found = board
-                .solution
-                .as_ref()
-                .and_then(|s| s.steps.as_ref())
-                .map(|steps| steps.len() >= game_config.minimum_moves_required as usize)
-                .unwrap_or(false);
-
-            if solution_found {
-                tracing::info!(
-                    request_id,
-                    "Solvable level found after {} attempts.",
-                    &board_count
-                );
-                break;
-            }
-        }
-
-        if game_config.board_only {
-            // After solving, replace the player's tile with Start in the layout,
-            // so JSON shows 'S' instead of 'P' in level layout.
-            let start: Point = board.layout.start.0;
-            board.layout.grid[start.row][start.col] = Tile::Start;
-        }
+    ) -> Result<Self, GlacierError> {
+        Board::generate_solvable_board_cancellable(
+            game_config,
+            request_id_opt,
+            &AtomicBool::new(false),
+        )
+    }
 
-        Ok(board)
+    // Like generate_solvable_board, but gives up as soon as `cancelled` is set (checked once per attempt).
+    pub fn generate_solvable_board_cancellable(
+        game_config: &GameConfig,
+        request_id_opt: Option<u64>,
+        cancelled: &AtomicBool,
+    ) -> Result<Self, GlacierError> {
+        Board::generate_solvable_board_with_progress(game_config, request_id_opt, cancelled, |_| {})
     }
 
     fn create_arrows(&self, start_position: bool, p: Point) -> String {
@@ -293,6 +322,13 @@ impl Board {
         let col_usize = col as usize;
         let row_usize = row as usize;
 
+        if let Some(arrow) = self.hint_arrow_at(Point {
+            col: col_usize,
+            row: row_usize,
+        }) {
+            return String::from(arrow);
+        }
+
         match self.layout.grid[row_usize][col_usize] {
             Tile::Wall | Tile::Rock => String::from("██"),
             Tile::Player => String::from("🟥"),
@@ -361,42 +397,13 @@ impl Board {
         result
     }
 
-    fn steps_in_direction(&self, direction: &Direction) -> u8 {
-        let (col_change, row_change): (isize, isize) = match direction {
-            Direction::Up => (0, -1),
-            Direction::Down => (0, 1),
-            Direction::Left => (-1, 0),
-            Direction::Right => (1, 0),
-        };
-
-        let mut current_position = self.player.0;
-        let mut steps: u8 = 0;
-
-        loop {
-            let next_col = current_position.col as isize + col_change;
-            let next_row = current_position.row as isize + row_change;
-
-            // Check level bounds
-            if next_col < 0
-                || next_row < 0
-                || next_col as usize >= self.layout.cols
-                || next_row as usize >= self.layout.rows
-            {
-                break;
-            }
-
-            // Check whether a wall or rock should block the player's movement
-            match self.layout.grid[next_row as usize][next_col as usize] {
-                Tile::Wall | Tile::Rock => break,
-                _ => {
-                    current_position.col = next_col as usize;
-                    current_position.row = next_row as usize;
-                    steps += 1;
-                }
-            }
-        }
+    fn steps_in_direction(&self, direction: &Direction) -> usize {
+        self.steps_from(self.player.0, direction)
+    }
 
-        steps
+    // Count how far a player resting at `from` would slide, without moving the player.
+    fn steps_from(&self, from: Point, direction: &Direction) -> usize {
+        slide_table::slide_length(&self.layout, from, *direction)
     }
 
     fn update_player_position(&mut self, new_row: usize, new_col: usize) {
@@ -408,7 +415,7 @@ impl Board {
             self.player.0.col = new_col;
             self.layout.grid[new_row][new_col] = Tile::Player;
 
-            if self.player_won() && !self.bot_is_solving {
+            if self.player_won() {
                 self.player_has_won = true;
             }
 
@@ -437,31 +444,41 @@ impl Board {
         }
     }
 
-    pub fn respond_to_input(&mut self, key_code: KeyCode) {
+    // Queue a slide as if the player pressed the matching key. Does nothing while still sliding.
+    pub fn queue_slide(&mut self, direction: Direction) {
         if !self.player_has_won {
-            let move_opt: Option<Move> = match key_code {
-                KeyCode::Char('w') | KeyCode::Up => self.create_slide_move(&Direction::Up),
-                KeyCode::Char('s') | KeyCode::Down => self.create_slide_move(&Direction::Down),
-                KeyCode::Char('a') | KeyCode::Left => self.create_slide_move(&Direction::Left),
-                KeyCode::Char('d') | KeyCode::Right => self.create_slide_move(&Direction::Right),
-                KeyCode::Char(' ') => Some(Move::Reset),
-                _ => None,
-            };
+            if let Some(mv) = self.create_slide_move(&direction) {
+                self.history.record(self.player.0);
+                self.move_queue.push_back(mv);
+            }
+        }
+    }
 
-            if let Some(mv) = move_opt {
-                if let Move::Reset = mv {
-                    self.move_queue.clear();
-                    if self.player.0 != self.layout.start.0 {
-                        // Only queue reset if the player is not already at the start.
-                        self.move_queue.push_back(mv);
-                    }
-                } else {
-                    self.move_queue.push_back(mv);
-                }
+    pub fn queue_reset(&mut self) {
+        if !self.player_has_won {
+            self.move_queue.clear();
+            self.history.clear();
+            if self.player.0 != self.layout.start.0 {
+                // Only queue reset if the player is not already at the start.
+                self.move_queue.push_back(Move::Reset);
             }
         }
     }
 
+    #[cfg(feature = "tui")]
+    pub fn respond_to_input(&mut self, key_code: KeyCode) {
+        match key_code {
+            KeyCode::Char('w') | KeyCode::Up => self.queue_slide(Direction::Up),
+            KeyCode::Char('s') | KeyCode::Down => self.queue_slide(Direction::Down),
+            KeyCode::Char('a') | KeyCode::Left => self.queue_slide(Direction::Left),
+            KeyCode::Char('d') | KeyCode::Right => self.queue_slide(Direction::Right),
+            KeyCode::Char(' ') => self.queue_reset(),
+            KeyCode::Char('u') | KeyCode::Char('U') => self.queue_undo(),
+            KeyCode::Char('r') | KeyCode::Char('R') => self.queue_redo(),
+            _ => {}
+        }
+    }
+
     pub fn move_player(&mut self, dir: Direction) {
         let (new_row, new_col) = match dir {
             Direction::Up => (self.player.0.row - 1, self.player.0.col),
@@ -480,9 +497,10 @@ impl Board {
         /* Pop the move queue, and respond to the move. This method is intended to be called
           within a callback function in the renderer.
         */
-        self.move_queue
-            .pop_front()
-            .map(|curr_move| match curr_move {
+        self.move_queue.pop_front().map(|curr_move| {
+            // Any movement makes the current hint stale.
+            self.hint = None;
+            match curr_move {
                 Move::SlidePlayer(mut slide) => {
                     // If the number of steps is greater than 1, modify the Slide object,
                     // and put it back on the front of the queue
@@ -495,52 +513,23 @@ impl Board {
                 Move::Reset => {
                     self.update_player_position(self.layout.start.0.row, self.layout.start.0.col)
                 }
-            })
-    }
-
-    fn solve(&mut self, max_depth: u16) {
-        let mut visited = HashSet::<Point>::new();
-        let mut solution = Solution::new();
-
-        self.bot_is_solving = true;
-
-        // Breadth-first search guarantees the first solution we find is the shortest (if there is a solution).
-        let mut bfs_queue = VecDeque::new();
-        bfs_queue.push_back((Vec::<Direction>::new(), self.layout.start.0));
-
-        while let Some((parent_prev, parent_pos)) = bfs_queue.pop_front() {
-            if parent_pos == self.layout.end.0 {
-                solution.steps = Some(parent_prev);
-                break;
-            } else if parent_prev.len() > max_depth as usize {
-                break;
-            } else if !visited.contains(&parent_pos) {
-                visited.insert(parent_pos);
-                solution.edges_traversed += 1;
-
-                // Find possible next moves
-                for direction in self.get_possible_moves(parent_prev.last()) {
-                    // Reset the player to the parent position
-                    self.update_player_position(parent_pos.row, parent_pos.col);
-                    let steps = self.steps_in_direction(&direction);
-                    if steps > 0 {
-                        let mut child_moves = parent_prev.clone();
-                        child_moves.push(direction);
-                        for _ in 0..steps {
-                            self.move_player(direction);
-                        }
-                        let child_position = self.player.0;
-                        bfs_queue.push_back((child_moves, child_position));
-                    }
-                }
             }
-        }
-        // After solving (or giving up due to the search depth), return the player to the start
-        self.update_player_position(self.layout.start.0.row, self.layout.start.0.col);
-        self.bot_is_solving = false;
-        self.player_has_won = false;
+        })
+    }
+
+    // Find the shortest solution from the start, searching the whole board.
+    pub fn solve(&self) -> Solution {
+        self.solve_with_max_depth(u16::MAX)
+    }
+
+    // Find the shortest solution from the start, giving up on paths longer than max_depth moves.
+    pub fn solve_with_max_depth(&self, max_depth: u16) -> Solution {
+        self.shortest_path_from_with_max_depth(self.layout.start.0, max_depth)
+    }
 
-        self.solution = Some(solution);
+    // How many distinct shortest solutions there are from the start (0 if there are none).
+    pub fn count_optimal_solutions(&self) -> u64 {
+        self.count_shortest_paths_from(self.layout.start.0, u16::MAX)
     }
 
     fn get_possible_moves(&self, previous_move: Option<&Direction>) -> Vec<Direction> {
@@ -559,30 +548,11 @@ impl Board {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn make_simple_board() -> Board {
-        /*
-        5x5 board so we have a 1-cell thick wall border and a 3x3 interior.
-        WWWWW
-        S   W
-        W R W
-        W   W
-        WWWEW
-        */
-        let rows = 5;
-        let cols = 5;
-
-        let start = Point { col: 0, row: 1 };
-        let end = Point { col: 3, row: 4 };
-
-        let rocks = vec![Point { col: 2, row: 2 }];
-
-        Board::new(rows, cols, start, end, rocks)
-    }
+    use test_boards::{simple_board, unsolvable_board};
 
     #[test]
     fn steps_in_direction_stops_before_wall() {
-        let board = make_simple_board();
+        let board = simple_board();
         /*
         WWWWW
         S123W // Three steps to the right
@@ -593,9 +563,19 @@ mod tests {
         assert_eq!(board.steps_in_direction(&Direction::Right), 3);
     }
 
+    #[test]
+    fn steps_from_counts_slides_longer_than_u8() {
+        let start = Point { col: 0, row: 1 };
+        let end = Point { col: 299, row: 1 };
+        let board = Board::new(3, 300, start, end, vec![]);
+
+        assert_eq!(board.steps_from(start, &Direction::Right), 299);
+        assert_eq!(board.slide_from(start, Direction::Right), Some(end));
+    }
+
     #[test]
     fn get_possible_moves_from_none_returns_all_directions() {
-        let board = make_simple_board();
+        let board = simple_board();
         let mut moves = board.get_possible_moves(None);
 
         moves.sort_by_key(|d| match d {
@@ -611,7 +591,7 @@ mod tests {
 
     #[test]
     fn get_possible_moves_after_vertical_move_only_horizontal() {
-        let board = make_simple_board();
+        let board = simple_board();
         let moves = board.get_possible_moves(Some(&Direction::Up));
 
         // After Up/Down, only Left/Right should be allowed.
@@ -622,7 +602,7 @@ mod tests {
 
     #[test]
     fn get_possible_moves_after_horizontal_move_only_vertical() {
-        let board = make_simple_board();
+        let board = simple_board();
         let moves = board.get_possible_moves(Some(&Direction::Left));
 
         // After Left/Right, only Up/Down should be allowed.
@@ -633,7 +613,7 @@ mod tests {
 
     #[test]
     fn process_move_reset_returns_player_to_start() {
-        let mut board = make_simple_board();
+        let mut board = simple_board();
 
         // Move the player away from the start.
         board.move_player(Direction::Right);
@@ -647,4 +627,124 @@ mod tests {
         // Player should be back at the start.
         assert_eq!(board.player.0, board.layout.start.0);
     }
+
+    #[test]
+    fn queue_slide_moves_player_until_blocked() {
+        let mut board = simple_board();
+
+        board.queue_slide(Direction::Right);
+        // A second slide is ignored while the first is still in progress.
+        board.queue_slide(Direction::Down);
+        while board.process_move().is_some() {}
+        assert_eq!(board.player.0, Point { col: 3, row: 1 });
+
+        board.queue_reset();
+        while board.process_move().is_some() {}
+        assert_eq!(board.player.0, board.layout.start.0);
+    }
+
+    #[test]
+    fn from_json_round_trips_generated_layout() {
+        let config = GameConfig {
+            board_only: true,
+            ..GameConfig::default()
+        };
+        let board = Board::generate_solvable_board(&config, None).unwrap();
+        let json = board.get_layout_json();
+
+        let loaded = Board::from_json(&json).unwrap();
+
+        assert_eq!(loaded.player.0, board.layout.start.0);
+        assert_eq!(loaded.seed(), board.seed());
+        assert_eq!(
+            loaded.solve().get_solution_string(),
+            board.solution.as_ref().unwrap().get_solution_string()
+        );
+    }
+
+    #[test]
+    fn from_ascii_parses_grid_and_solves() {
+        let text = "\nWWWWW\nS   W\nW R W\nW   W\nWWWEW\n\n";
+        let board = Board::from_ascii(text).unwrap();
+
+        assert_eq!(board.layout.start.0, Point { col: 0, row: 1 });
+        assert_eq!(board.layout.end.0, Point { col: 3, row: 4 });
+        assert_eq!(board.layout.rocks.len(), 1);
+
+        let solution = board.solve();
+        assert_eq!(solution.get_solution_string(), Some("RD".to_string()));
+        // Solving must leave the player back on the start.
+        assert_eq!(board.player.0, board.layout.start.0);
+    }
+
+    #[test]
+    fn from_text_detects_json_and_ascii() {
+        let ascii = "WWWWW\nS   W\nW R W\nW   W\nWWWEW";
+        let json = Board::from_ascii(ascii).unwrap().get_layout_json();
+
+        assert!(Board::from_text(ascii).is_ok());
+        assert!(Board::from_text(&json).is_ok());
+    }
+
+    #[test]
+    fn solve_reports_unsolvable_board() {
+        let board = unsolvable_board();
+
+        let solution = board.solve();
+        assert!(solution.steps.is_none());
+        assert_eq!(solution.edges_traversed, 2);
+    }
+
+    #[test]
+    fn from_json_rejects_malformed_json() {
+        let err = Board::from_json("{ \"rows\": 5 }").err().unwrap();
+        assert!(matches!(err, GlacierError::InvalidBoard(_)));
+        assert!(
+            err.to_string().starts_with("Invalid board JSON"),
+            "unexpected error: {err}"
+        );
+    }
+
+    #[test]
+    fn generate_solvable_board_cancellable_stops_when_cancelled() {
+        let config = GameConfig {
+            board_only: true,
+            ..GameConfig::default()
+        };
+        let cancelled = AtomicBool::new(true);
+
+        let result = Board::generate_solvable_board_cancellable(&config, None, &cancelled);
+        assert!(matches!(result, Err(GlacierError::GenerationCancelled)));
+    }
+
+    #[test]
+    fn generate_random_board_is_deterministic_for_a_seed() {
+        let config = GameConfig::default();
+
+        let first = Board::generate_random_board(&config, 7);
+        let second = Board::generate_random_board(&config, 7);
+
+        assert_eq!(first.get_layout_json(), second.get_layout_json());
+        assert_eq!(first.seed(), Some(7));
+    }
+
+    #[test]
+    fn generate_solvable_board_can_be_regenerated_from_its_seed() {
+        let config = GameConfig {
+            seed: Some(1234),
+            board_only: true,
+            ..GameConfig::default()
+        };
+        let board = Board::generate_solvable_board(&config, None).unwrap();
+
+        // Regenerating with the board's own seed finds the same board on the first attempt.
+        let replay_config = GameConfig {
+            seed: board.seed(),
+            ..config
+        };
+        let replayed = Board::generate_solvable_board(&replay_config, None).unwrap();
+
+        assert_eq!(board.get_layout_json(), replayed.get_layout_json());
+        assert_eq!(replayed.generation_attempts, 1);
+    }
 }
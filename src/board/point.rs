@@ -14,6 +14,17 @@ impl serde::Serialize for Point {
     }
 }
 
+impl<'de> serde::Deserialize<'de> for Point {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        // Deserialize from [col, row]
+        let (col, row) = <(usize, usize)>::deserialize(deserializer)?;
+        Ok(Point { col, row })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -25,4 +36,15 @@ mod tests {
         let json = serde_json::to_string(&p).unwrap();
         assert_eq!(json, "[2,5]");
     }
+
+    #[test]
+    fn point_deserializes_from_col_row_array() {
+        let p: Point = serde_json::from_str("[2,5]").unwrap();
+        assert_eq!(p, Point { col: 2, row: 5 });
+    }
+
+    #[test]
+    fn point_rejects_negative_coordinates() {
+        assert!(serde_json::from_str::<Point>("[-1,5]").is_err());
+    }
 }
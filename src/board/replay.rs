@@ -0,0 +1,85 @@
+use super::{Board, Direction, Point};
+
+// The outcome of replaying a sequence of moves from the start of a board.
+#[derive(Debug, PartialEq)]
+pub struct Replay {
+    pub final_position: Point,
+    pub reached_end: bool,
+    // Only slides that actually moved the player are counted, matching the terminal game.
+    pub moves: usize,
+}
+
+impl Board {
+    // Replay moves from the start with the same slide physics as the game. As in the game, the
+    // player stops once the end is reached, so any remaining moves are ignored.
+    pub fn replay(&self, moves: &[Direction]) -> Replay {
+        let mut position = self.layout.start.0;
+        let mut move_count = 0;
+
+        for direction in moves {
+            if position == self.layout.end.0 {
+                break;
+            }
+
+            if let Some(next) = self.slide_from(position, *direction) {
+                position = next;
+                move_count += 1;
+            }
+        }
+
+        Replay {
+            final_position: position,
+            reached_end: position == self.layout.end.0,
+            moves: move_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::simple_board;
+
+    #[test]
+    fn replay_of_solution_reaches_end() {
+        let board = simple_board();
+        let replay = board.replay(&[Direction::Right, Direction::Down]);
+
+        assert_eq!(
+            replay,
+            Replay {
+                final_position: Point { col: 3, row: 4 },
+                reached_end: true,
+                moves: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn replay_ignores_blocked_moves() {
+        let board = simple_board();
+        // Up is blocked by the border wall, so it does not count as a move.
+        let replay = board.replay(&[Direction::Up, Direction::Right, Direction::Left]);
+
+        assert_eq!(replay.final_position, Point { col: 0, row: 1 });
+        assert!(!replay.reached_end);
+        assert_eq!(replay.moves, 2);
+    }
+
+    #[test]
+    fn replay_stops_at_end() {
+        let board = simple_board();
+        let replay = board.replay(&[Direction::Right, Direction::Down, Direction::Up]);
+
+        assert!(replay.reached_end);
+        assert_eq!(replay.moves, 2);
+    }
+
+    #[test]
+    fn replay_does_not_move_the_player() {
+        let board = simple_board();
+        board.replay(&[Direction::Right]);
+
+        assert_eq!(board.player.0, Point { col: 0, row: 1 });
+    }
+}
@@ -0,0 +1,148 @@
+use super::Board;
+
+pub const MAX_STARS: u8 = 3;
+
+// How well a finished level was played, compared with its shortest solution (par).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LevelScore {
+    pub moves: u32,
+    pub par: u32,
+    pub hints: u32,
+    pub stars: u8,
+}
+
+impl LevelScore {
+    // 3 stars at par, 2 stars up to 50% over par, otherwise 1 star. Each hint costs a star,
+    // but finishing a level always earns at least one.
+    pub fn new(moves: u32, par: u32, hints: u32) -> Self {
+        let stars: u8 = if moves <= par {
+            3
+        } else if moves * 2 <= par * 3 {
+            2
+        } else {
+            1
+        };
+        let stars = stars
+            .saturating_sub(hints.min(u32::from(MAX_STARS)) as u8)
+            .max(1);
+
+        LevelScore {
+            moves,
+            par,
+            hints,
+            stars,
+        }
+    }
+
+    pub fn is_optimal(&self) -> bool {
+        self.moves <= self.par
+    }
+
+    pub fn star_string(&self) -> String {
+        (0..MAX_STARS)
+            .map(|i| if i < self.stars { '★' } else { '☆' })
+            .collect()
+    }
+}
+
+impl Board {
+    // Every slide the player has made on this level. Slides that were undone, or made before a
+    // reset, still count, so trial and error cannot earn a perfect score. Undo and redo themselves
+    // are free.
+    pub fn moves_made(&self) -> u32 {
+        self.history.slides()
+    }
+
+    // Length of the shortest solution, if the board has been solved.
+    pub fn par(&self) -> Option<u32> {
+        self.solution
+            .as_ref()
+            .and_then(|s| s.steps.as_ref())
+            .map(|steps| steps.len() as u32)
+    }
+
+    // Only available once the player has reached the end.
+    pub fn score(&self) -> Option<LevelScore> {
+        if !self.player_has_won {
+            return None;
+        }
+        self.par()
+            .map(|par| LevelScore::new(self.moves_made(), par, self.hints_used()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::simple_board;
+    use crate::board::Direction;
+
+    #[test]
+    fn stars_drop_as_moves_exceed_par() {
+        assert_eq!(LevelScore::new(7, 7, 0).stars, 3);
+        assert_eq!(LevelScore::new(8, 7, 0).stars, 2);
+        assert_eq!(LevelScore::new(10, 7, 0).stars, 2);
+        assert_eq!(LevelScore::new(11, 7, 0).stars, 1);
+    }
+
+    #[test]
+    fn hints_cost_stars_down_to_one() {
+        assert_eq!(LevelScore::new(7, 7, 1).stars, 2);
+        assert_eq!(LevelScore::new(8, 7, 1).stars, 1);
+        assert_eq!(LevelScore::new(7, 7, 5).stars, 1);
+    }
+
+    #[test]
+    fn star_string_shows_earned_and_missed_stars() {
+        assert_eq!(LevelScore::new(3, 2, 0).star_string(), "★★☆");
+        assert!(LevelScore::new(2, 2, 0).is_optimal());
+    }
+
+    #[test]
+    fn score_compares_moves_with_solution() {
+        let mut board = simple_board();
+        board.solution = Some(board.solve());
+        assert_eq!(board.par(), Some(2));
+
+        for direction in [Direction::Right, Direction::Left, Direction::Right] {
+            board.queue_slide(direction);
+            while board.process_move().is_some() {}
+        }
+        assert_eq!(board.moves_made(), 3);
+        assert!(board.score().is_none());
+
+        board.queue_slide(Direction::Down);
+        while board.process_move().is_some() {}
+
+        let score = board.score().unwrap();
+        assert_eq!(
+            (score.moves, score.par, score.hints, score.stars),
+            (4, 2, 0, 1)
+        );
+    }
+
+    #[test]
+    fn undone_and_reset_slides_still_count() {
+        let mut board = simple_board();
+        board.solution = Some(board.solve());
+        let finish = |board: &mut Board| while board.process_move().is_some() {};
+
+        // Try the optimal first slide, undo it, try it again and reset, then play RD.
+        board.queue_slide(Direction::Right);
+        finish(&mut board);
+        board.queue_undo();
+        finish(&mut board);
+        board.queue_slide(Direction::Right);
+        finish(&mut board);
+        board.queue_reset();
+        finish(&mut board);
+        for direction in [Direction::Right, Direction::Down] {
+            board.queue_slide(direction);
+            finish(&mut board);
+        }
+
+        let score = board.score().unwrap();
+        assert_eq!((score.moves, score.par, score.stars), (4, 2, 1));
+        assert!(!score.is_optimal());
+    }
+}
@@ -0,0 +1,320 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::slide_table::{point_after, slide_length, SlideTable, NONE};
+use super::{Board, Direction, Point, Solution};
+
+// Moves worth trying after `previous`. Sliding the same way again goes nowhere, and sliding
+// straight back never shortens a solution.
+fn next_moves(previous: Option<Direction>) -> &'static [Direction] {
+    match previous {
+        Some(Direction::Up) | Some(Direction::Down) => &[Direction::Right, Direction::Left],
+        Some(Direction::Left) | Some(Direction::Right) => &[Direction::Up, Direction::Down],
+        None => &Direction::ALL,
+    }
+}
+
+// Searches over resting positions. None of these move the player or touch the grid, so they are
+// safe to call in the middle of a game.
+impl Board {
+    // Where a player resting at `from` stops after sliding in `direction`, or None if the slide is
+    // blocked straight away.
+    pub fn slide_from(&self, from: Point, direction: Direction) -> Option<Point> {
+        let steps = slide_length(&self.layout, from, direction);
+        (steps > 0).then(|| point_after(from, direction, steps))
+    }
+
+    // Find the shortest path from any resting position to the end. The number of moves to the end
+    // is the length of the solution's steps.
+    pub fn shortest_path_from(&self, from: Point) -> Solution {
+        self.shortest_path_from_with_max_depth(from, u16::MAX)
+    }
+
+    // Same as shortest_path_from, giving up on paths longer than max_depth moves.
+    pub fn shortest_path_from_with_max_depth(&self, from: Point, max_depth: u16) -> Solution {
+        let mut table = SlideTable::new(&self.layout);
+        let end = table.cell(self.layout.end.0);
+        let mut visited = vec![0u64; table.len().div_ceil(64)];
+        // The cell and direction each expanded cell was first reached from, so the path is only
+        // built once, for the end.
+        let mut parents = vec![(NONE, None::<Direction>); table.len()];
+        let mut solution = Solution::new();
+
+        // Breadth-first search guarantees the first solution we find is the shortest (if there is a solution).
+        // Each entry is (cell, parent cell, last move, moves made).
+        let mut bfs_queue = VecDeque::new();
+        bfs_queue.push_back((table.cell(from), NONE, None, 0u32));
+
+        while let Some((cell, parent, last_move, depth)) = bfs_queue.pop_front() {
+            if cell == end {
+                let mut steps: Vec<Direction> = last_move.into_iter().collect();
+                let mut previous = parent;
+                while previous != NONE {
+                    let (grandparent, direction) = parents[previous as usize];
+                    steps.extend(direction);
+                    previous = grandparent;
+                }
+                steps.reverse();
+                solution.steps = Some(steps);
+                break;
+            } else if depth > u32::from(max_depth) {
+                break;
+            } else if visited[cell as usize / 64] & (1 << (cell % 64)) == 0 {
+                visited[cell as usize / 64] |= 1 << (cell % 64);
+                parents[cell as usize] = (parent, last_move);
+                solution.edges_traversed += 1;
+
+                for &direction in next_moves(last_move) {
+                    if let Some(child) = table.slide(cell, direction) {
+                        bfs_queue.push_back((child, cell, Some(direction), depth + 1));
+                    }
+                }
+            }
+        }
+
+        solution
+    }
+
+    // How many distinct shortest solutions lead from `from` to the end, or 0 if none is at most
+    // max_depth moves long. Unlike shortest_path_from, the search carries on through the level of
+    // the end, adding up the ways each position can be reached in as few moves as possible.
+    pub fn count_shortest_paths_from(&self, from: Point, max_depth: u16) -> u64 {
+        let mut table = SlideTable::new(&self.layout);
+        let end = table.cell(self.layout.end.0) as usize;
+        let mut depths = vec![NONE; table.len()];
+        let mut counts = vec![0u64; table.len()];
+        let start = table.cell(from) as usize;
+        depths[start] = 0;
+        counts[start] = 1;
+
+        // Positions leave the queue in order of depth, so every shorter way into a position has
+        // been counted before the position itself is expanded.
+        let mut bfs_queue = VecDeque::from([start]);
+        while let Some(cell) = bfs_queue.pop_front() {
+            let depth = depths[cell];
+            if depth >= depths[end] || depth >= u32::from(max_depth) {
+                break;
+            }
+
+            // Every direction is tried: a shortest solution never slides the same way twice or
+            // straight back, so pruning with next_moves would not change the count.
+            for direction in Direction::ALL {
+                let Some(child) = table.slide(cell as u32, direction) else {
+                    continue;
+                };
+                let child = child as usize;
+                if depths[child] == NONE {
+                    depths[child] = depth + 1;
+                    bfs_queue.push_back(child);
+                }
+                if depths[child] == depth + 1 {
+                    counts[child] = counts[child].saturating_add(counts[cell]);
+                }
+            }
+        }
+
+        counts[end]
+    }
+
+    // The original search, which walks every slide tile by tile and clones the path for every
+    // queued position. Kept to check the slide-table search against and to benchmark it.
+    #[doc(hidden)]
+    pub fn shortest_path_from_reference(&self, from: Point, max_depth: u16) -> Solution {
+        let mut visited = HashSet::<Point>::new();
+        let mut solution = Solution::new();
+
+        // Breadth-first search guarantees the first solution we find is the shortest (if there is a solution).
+        let mut bfs_queue = VecDeque::new();
+        bfs_queue.push_back((Vec::<Direction>::new(), from));
+
+        while let Some((parent_prev, parent_pos)) = bfs_queue.pop_front() {
+            if parent_pos == self.layout.end.0 {
+                solution.steps = Some(parent_prev);
+                break;
+            } else if parent_prev.len() > max_depth as usize {
+                break;
+            } else if !visited.contains(&parent_pos) {
+                visited.insert(parent_pos);
+                solution.edges_traversed += 1;
+
+                // Find possible next moves
+                for direction in self.get_possible_moves(parent_prev.last()) {
+                    if let Some(child_position) = self.slide_from(parent_pos, direction) {
+                        let mut child_moves = parent_prev.clone();
+                        child_moves.push(direction);
+                        bfs_queue.push_back((child_moves, child_position));
+                    }
+                }
+            }
+        }
+
+        solution
+    }
+
+    // Distance to the end (in moves) from every resting position the player can reach from the
+    // start, or None for positions the end cannot be reached from.
+    pub fn distance_map(&self) -> HashMap<Point, Option<u32>> {
+        let mut table = SlideTable::new(&self.layout);
+        let start = table.cell(self.layout.start.0) as usize;
+        let end = table.cell(self.layout.end.0) as usize;
+
+        // Explore every reachable position, remembering which positions lead to each one.
+        let mut predecessors: Vec<Option<Vec<u32>>> = vec![None; table.len()];
+        predecessors[start] = Some(Vec::new());
+        let mut queue = VecDeque::from([start]);
+
+        while let Some(cell) = queue.pop_front() {
+            // The game is over once the end is reached.
+            if cell == end {
+                continue;
+            }
+            for direction in Direction::ALL {
+                if let Some(next) = table.slide(cell as u32, direction) {
+                    let leads_here = &mut predecessors[next as usize];
+                    if leads_here.is_none() {
+                        queue.push_back(next as usize);
+                    }
+                    leads_here.get_or_insert_with(Vec::new).push(cell as u32);
+                }
+            }
+        }
+
+        // Then walk backwards from the end to find each position's distance.
+        let mut distances = vec![NONE; table.len()];
+        if predecessors[end].is_some() {
+            distances[end] = 0;
+            let mut queue = VecDeque::from([end]);
+
+            while let Some(cell) = queue.pop_front() {
+                for &previous in predecessors[cell].iter().flatten() {
+                    if distances[previous as usize] == NONE {
+                        distances[previous as usize] = distances[cell] + 1;
+                        queue.push_back(previous as usize);
+                    }
+                }
+            }
+        }
+
+        (0..table.len())
+            .filter(|&cell| predecessors[cell].is_some())
+            .map(|cell| {
+                let distance = (distances[cell] != NONE).then_some(distances[cell]);
+                (table.point(cell as u32), distance)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::{board_with_dead_end, board_with_two_solutions};
+    use crate::game_state::GameConfig;
+
+    #[test]
+    fn shortest_path_from_any_position() {
+        let board = board_with_dead_end();
+
+        let from_start = board.shortest_path_from(Point { col: 5, row: 2 });
+        let from_middle = board.shortest_path_from(Point { col: 2, row: 2 });
+        assert_eq!(from_start.get_solution_string().as_deref(), Some("LD"));
+        assert_eq!(from_middle.get_solution_string().as_deref(), Some("D"));
+
+        let from_dead_end = board.shortest_path_from(Point { col: 4, row: 1 });
+        assert!(from_dead_end.steps.is_none());
+    }
+
+    #[test]
+    fn shortest_path_from_does_not_move_the_player() {
+        let board = board_with_dead_end();
+        board.shortest_path_from(Point { col: 2, row: 2 });
+
+        assert_eq!(board.player.0, Point { col: 5, row: 2 });
+    }
+
+    #[test]
+    fn distance_map_covers_reachable_positions() {
+        let board = board_with_dead_end();
+        let distances = board.distance_map();
+
+        assert_eq!(distances[&Point { col: 5, row: 2 }], Some(2));
+        assert_eq!(distances[&Point { col: 2, row: 2 }], Some(1));
+        assert_eq!(distances[&Point { col: 2, row: 5 }], Some(0));
+        assert_eq!(distances[&Point { col: 4, row: 1 }], None);
+        // A rock can never be a resting position.
+        assert!(!distances.contains_key(&Point { col: 1, row: 2 }));
+
+        // Every finite distance agrees with the shortest path from that position.
+        for (position, distance) in &distances {
+            let steps = board.shortest_path_from(*position).steps;
+            assert_eq!(*distance, steps.map(|s| s.len() as u32));
+        }
+    }
+
+    #[test]
+    fn slide_table_search_matches_reference_search() {
+        for difficulty in ["easy", "medium", "hard", "extreme"] {
+            let config = GameConfig::get_config_from_difficulty(difficulty).unwrap();
+            for seed in 0..50 {
+                let board = Board::generate_random_board(&config, seed);
+                let from = board.layout.start.0;
+                for max_depth in [3, config.minimum_moves_required + 2, u16::MAX] {
+                    let fast = board.shortest_path_from_with_max_depth(from, max_depth);
+                    let reference = board.shortest_path_from_reference(from, max_depth);
+                    assert_eq!(fast.steps, reference.steps, "{difficulty} seed {seed}");
+                    assert_eq!(fast.edges_traversed, reference.edges_traversed);
+                }
+            }
+        }
+    }
+
+    // Try every sequence of `moves` slides from `from`, counting those that finish at the end.
+    fn count_paths_by_brute_force(board: &Board, from: Point, moves: u32) -> u64 {
+        if from == board.layout.end.0 {
+            return u64::from(moves == 0);
+        }
+        if moves == 0 {
+            return 0;
+        }
+        Direction::ALL
+            .into_iter()
+            .filter_map(|direction| board.slide_from(from, direction))
+            .map(|next| count_paths_by_brute_force(board, next, moves - 1))
+            .sum()
+    }
+
+    #[test]
+    fn count_shortest_paths_from_finds_every_optimal_solution() {
+        // DLUL and DRUL both take 4 moves.
+        let board = board_with_two_solutions();
+        let start = board.layout.start.0;
+        assert_eq!(board.count_optimal_solutions(), 2);
+        // Neither solution fits in 3 moves.
+        assert_eq!(board.count_shortest_paths_from(start, 3), 0);
+
+        let board = board_with_dead_end();
+        assert_eq!(board.count_optimal_solutions(), 1);
+        assert_eq!(
+            board.count_shortest_paths_from(Point { col: 4, row: 1 }, u16::MAX),
+            0
+        );
+        assert_eq!(
+            board.count_shortest_paths_from(board.layout.end.0, u16::MAX),
+            1
+        );
+    }
+
+    #[test]
+    fn count_shortest_paths_from_matches_brute_force() {
+        let config = GameConfig::get_config_from_difficulty("easy").unwrap();
+        for seed in 0..50 {
+            let board = Board::generate_random_board(&config, seed);
+            let expected = match board.solve().steps {
+                Some(steps) => {
+                    count_paths_by_brute_force(&board, board.layout.start.0, steps.len() as u32)
+                }
+                None => 0,
+            };
+            assert_eq!(board.count_optimal_solutions(), expected, "seed {seed}");
+        }
+    }
+}
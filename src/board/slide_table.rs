@@ -0,0 +1,141 @@
+use super::{BoardLayout, Direction, Point, Tile};
+
+// Marks a blocked slide, and a missing parent in the solver.
+pub(super) const NONE: u32 = u32::MAX;
+// Marks a slide that has not been worked out yet.
+const UNKNOWN: u32 = u32::MAX - 1;
+
+// Where a slide in each direction stops, for every tile on the board. Cells are numbered
+// row * cols + col, and directions are indexed in Direction::ALL order.
+//
+// Most random boards are rejected after a handful of moves, so entries are filled in the first
+// time a slide is asked for rather than for the whole board up front. Every tile a slide passes
+// over stops in the same place, so one walk fills in the whole run.
+pub(super) struct SlideTable<'a> {
+    layout: &'a BoardLayout,
+    destinations: Vec<[u32; 4]>,
+}
+
+// How many tiles a player resting at `from` slides before a wall, a rock or the edge of the board
+// stops them. The game, the solvers and the table all slide with this.
+pub(super) fn slide_length(layout: &BoardLayout, from: Point, direction: Direction) -> usize {
+    // The number of tiles to the edge of the board.
+    let room = match direction {
+        Direction::Up => from.row,
+        Direction::Down => layout.rows.saturating_sub(from.row + 1),
+        Direction::Left => from.col,
+        Direction::Right => layout.cols.saturating_sub(from.col + 1),
+    };
+
+    (1..=room)
+        .take_while(|&steps| {
+            let next = point_after(from, direction, steps);
+            !matches!(layout.grid[next.row][next.col], Tile::Wall | Tile::Rock)
+        })
+        .count()
+}
+
+// The tile `steps` tiles away from `from` in `direction`.
+pub(super) fn point_after(from: Point, direction: Direction, steps: usize) -> Point {
+    match direction {
+        Direction::Up => Point {
+            row: from.row - steps,
+            ..from
+        },
+        Direction::Down => Point {
+            row: from.row + steps,
+            ..from
+        },
+        Direction::Left => Point {
+            col: from.col - steps,
+            ..from
+        },
+        Direction::Right => Point {
+            col: from.col + steps,
+            ..from
+        },
+    }
+}
+
+fn direction_index(direction: Direction) -> usize {
+    match direction {
+        Direction::Up => 0,
+        Direction::Right => 1,
+        Direction::Down => 2,
+        Direction::Left => 3,
+    }
+}
+
+impl<'a> SlideTable<'a> {
+    pub(super) fn new(layout: &'a BoardLayout) -> Self {
+        SlideTable {
+            layout,
+            destinations: vec![[UNKNOWN; 4]; layout.rows * layout.cols],
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.destinations.len()
+    }
+
+    pub(super) fn cell(&self, point: Point) -> u32 {
+        (point.row * self.layout.cols + point.col) as u32
+    }
+
+    pub(super) fn point(&self, cell: u32) -> Point {
+        let cell = cell as usize;
+        Point {
+            col: cell % self.layout.cols,
+            row: cell / self.layout.cols,
+        }
+    }
+
+    // Same as Board::slide_from, on cell numbers.
+    pub(super) fn slide(&mut self, cell: u32, direction: Direction) -> Option<u32> {
+        let index = direction_index(direction);
+        if self.destinations[cell as usize][index] == UNKNOWN {
+            self.fill_run(cell as usize, direction);
+        }
+
+        let stop = self.destinations[cell as usize][index];
+        (stop != NONE).then_some(stop)
+    }
+
+    fn fill_run(&mut self, cell: usize, direction: Direction) {
+        let from = self.point(cell as u32);
+        let steps = slide_length(self.layout, from, direction);
+
+        let index = direction_index(direction);
+        let stop = self.cell(point_after(from, direction, steps));
+        for step in 0..steps {
+            let passed = self.cell(point_after(from, direction, step));
+            self.destinations[passed as usize][index] = stop;
+        }
+        self.destinations[stop as usize][index] = NONE;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::test_boards::board_with_dead_end;
+
+    #[test]
+    fn slide_table_matches_walking_each_slide() {
+        let board = board_with_dead_end();
+        let mut table = SlideTable::new(&board.layout);
+
+        for row in 0..board.layout.rows {
+            for col in 0..board.layout.cols {
+                let from = Point { col, row };
+                if matches!(board.layout.grid[row][col], Tile::Wall | Tile::Rock) {
+                    continue;
+                }
+                for direction in Direction::ALL {
+                    let expected = board.slide_from(from, direction).map(|p| table.cell(p));
+                    assert_eq!(table.slide(table.cell(from), direction), expected);
+                }
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use super::direction::Direction;
 
+#[derive(Default)]
 pub struct Solution {
     pub steps: Option<Vec<Direction>>,
     pub edges_traversed: u32,
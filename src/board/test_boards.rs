@@ -0,0 +1,62 @@
+// Hand-drawn boards shared by the tests of the board modules.
+use super::{Board, Point};
+
+/*
+5x5 board so we have a 1-cell thick wall border and a 3x3 interior.
+WWWWW
+S   W
+W R W
+W   W
+WWWEW
+The shortest solution is RD.
+*/
+pub(super) fn simple_board() -> Board {
+    let start = Point { col: 0, row: 1 };
+    let end = Point { col: 3, row: 4 };
+    let rocks = vec![Point { col: 2, row: 2 }];
+
+    Board::new(5, 5, start, end, rocks)
+}
+
+/*
+WWWWWW
+W    W
+WR   S
+W    W
+W    W
+WWEWWW
+The shortest solution is LD. From the top right corner, the end can never be reached again.
+*/
+pub(super) fn board_with_dead_end() -> Board {
+    Board::from_ascii("WWWWWW\nW    W\nWR   S\nW    W\nW    W\nWWEWWW").unwrap()
+}
+
+/*
+WWSWWWW
+E     W
+W     W
+W     W
+W     W
+WRR   W
+WWWWWWW
+DLUL and DRUL both take 4 moves.
+*/
+pub(super) fn board_with_two_solutions() -> Board {
+    Board::from_ascii("WWSWWWW\nE     W\nW     W\nW     W\nW     W\nWRR   W\nWWWWWWW").unwrap()
+}
+
+/*
+WWWWW
+S R W
+WR  W
+W   W
+WWWEW
+The player can only slide between the start and the tile next to it.
+*/
+pub(super) fn unsolvable_board() -> Board {
+    let start = Point { col: 0, row: 1 };
+    let end = Point { col: 3, row: 4 };
+    let rocks = vec![Point { col: 2, row: 1 }, Point { col: 1, row: 2 }];
+
+    Board::new(5, 5, start, end, rocks)
+}
@@ -1,17 +1,17 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 use super::point::Point;
 
 #[derive(Debug, Clone, Serialize)]
 pub struct Player(pub Point);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Start(pub Point);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct End(pub Point);
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Rock(pub Point);
 
 #[derive(Debug, Clone)]
@@ -35,6 +35,18 @@ impl Tile {
             Tile::Ice => ' ', // So when the board is serialized to JSON, there is a gap.
         }
     }
+
+    pub fn from_char(c: char) -> Option<Tile> {
+        match c {
+            'W' => Some(Tile::Wall),
+            'R' => Some(Tile::Rock),
+            'S' => Some(Tile::Start),
+            'E' => Some(Tile::End),
+            'P' => Some(Tile::Player),
+            ' ' => Some(Tile::Ice),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -50,4 +62,24 @@ mod tests {
         assert_eq!(Tile::Player.as_char(), 'P');
         assert_eq!(Tile::Ice.as_char(), ' ');
     }
+
+    #[test]
+    fn tile_from_char_round_trips() {
+        for tile in [
+            Tile::Wall,
+            Tile::Rock,
+            Tile::Start,
+            Tile::End,
+            Tile::Player,
+            Tile::Ice,
+        ] {
+            let c = tile.as_char();
+            assert_eq!(Tile::from_char(c).map(|t| t.as_char()), Some(c));
+        }
+    }
+
+    #[test]
+    fn tile_from_char_rejects_unknown_chars() {
+        assert!(Tile::from_char('x').is_none());
+    }
 }
@@ -0,0 +1,106 @@
+use std::{error::Error, fmt, io};
+
+#[derive(Debug)]
+pub enum GlacierError {
+    // No board met the requirements within the attempt budget.
+    GenerationExhausted { attempts: u32 },
+    // Generation was stopped by the caller (e.g. a server request timed out).
+    GenerationCancelled,
+    InvalidConfig(String),
+    InvalidBoard(String),
+    Io { context: String, source: io::Error },
+    Terminal(io::Error),
+}
+
+impl GlacierError {
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        GlacierError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    // Process exit code for the CLI. 1 is left for unexpected errors, and 2 matches clap's usage errors.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            GlacierError::InvalidConfig(_) => 2,
+            GlacierError::InvalidBoard(_) => 3,
+            GlacierError::GenerationExhausted { .. } => 4,
+            GlacierError::GenerationCancelled => 5,
+            GlacierError::Io { .. } => 6,
+            GlacierError::Terminal(_) => 7,
+        }
+    }
+}
+
+impl fmt::Display for GlacierError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GlacierError::GenerationExhausted { attempts } => write!(
+                f,
+                "Could not find solvable level after {} attempts.",
+                thousands::Separable::separate_with_commas(attempts)
+            ),
+            GlacierError::GenerationCancelled => f.write_str("Level generation was cancelled."),
+            GlacierError::InvalidConfig(msg) | GlacierError::InvalidBoard(msg) => f.write_str(msg),
+            GlacierError::Io { context, source } => write!(f, "{}: {}", context, source),
+            GlacierError::Terminal(source) => write!(f, "Terminal error: {}", source),
+        }
+    }
+}
+
+impl Error for GlacierError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            GlacierError::Io { source, .. } | GlacierError::Terminal(source) => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let errors = [
+            GlacierError::GenerationExhausted { attempts: 1 },
+            GlacierError::GenerationCancelled,
+            GlacierError::InvalidConfig(String::new()),
+            GlacierError::InvalidBoard(String::new()),
+            GlacierError::io("", io::Error::other("")),
+            GlacierError::Terminal(io::Error::other("")),
+        ];
+
+        let mut codes: Vec<u8> = errors.iter().map(GlacierError::exit_code).collect();
+        codes.sort();
+        codes.dedup();
+        assert_eq!(codes.len(), errors.len());
+        assert!(!codes.contains(&0) && !codes.contains(&1));
+    }
+
+    #[test]
+    fn display_formats_attempts_with_separators() {
+        let err = GlacierError::GenerationExhausted {
+            attempts: 1_000_000,
+        };
+        assert_eq!(
+            err.to_string(),
+            "Could not find solvable level after 1,000,000 attempts."
+        );
+    }
+
+    #[test]
+    fn io_errors_include_context_and_source() {
+        let err = GlacierError::io(
+            "Could not read board file 'x.json'",
+            io::Error::new(io::ErrorKind::NotFound, "not found"),
+        );
+        assert_eq!(
+            err.to_string(),
+            "Could not read board file 'x.json': not found"
+        );
+        assert!(err.source().is_some());
+    }
+}
@@ -1,13 +1,17 @@
-use std::error::Error;
+use std::{io, sync::atomic::AtomicBool, time::Instant};
 
 use crossterm::event::Event;
 use crossterm::event::{Event::Key, KeyCode, KeyEvent, KeyEventKind, KeyEventState, KeyModifiers};
-use crossterm::terminal::disable_raw_mode;
+use crossterm::execute;
+use crossterm::style::Print;
+use crossterm::terminal::{self, disable_raw_mode, Clear, ClearType};
+use thousands::Separable;
 
-use crate::board::Board;
-use crate::game_state::GameState;
-use crate::renderer::Renderer;
-use crate::system::{clear_terminal, exit_game, respond_to_input};
+use crate::board::{Board, GenerationProgress};
+use crate::error::GlacierError;
+use crate::game_state::{GameConfig, GameState};
+use crate::renderer::{Renderer, SceneEnd};
+use crate::system::{clear_terminal, respond_to_input};
 
 pub fn get_introduction_section() -> Vec<String> {
     const INTRO: &str = r"Welcome to
@@ -19,7 +23,9 @@ pub fn get_introduction_section() -> Vec<String> {
   
   Use 'WASD' or the arrow keys to move.
   Press 'SPACE' to restart.
+  Press 'U' or 'u' to undo a move, and 'R' or 'r' to redo it.
   Press 'V' or 'v' to change the view.
+  Press 'H' or 'h' for a hint (each hint costs a star).
   Press 'G' or 'g' to give up and show the solution.
   Press 'Q' or 'Ctrl-C' to exit.
 ";
@@ -27,7 +33,55 @@ pub fn get_introduction_section() -> Vec<String> {
     INTRO.lines().map(str::to_owned).collect()
 }
 
-fn play_next_input_handler(play_again_signal: &mut bool) -> Result<(), Box<dyn Error>> {
+// A single line, redrawn in place while the level generates.
+fn progress_line(progress: &GenerationProgress, moves_required: u16) -> String {
+    const WIDTH: usize = 30;
+    let filled = (progress.fraction() * WIDTH as f64).round() as usize;
+    let longest = progress
+        .best_moves
+        .map_or("-".to_string(), |moves| moves.to_string());
+
+    format!(
+        "  Generating level [{}{}] {} boards tried in {:.1}s (longest solution {}/{} moves)",
+        "#".repeat(filled),
+        "-".repeat(WIDTH - filled),
+        progress.attempts.separate_with_commas(),
+        progress.elapsed.as_secs_f64(),
+        longest,
+        moves_required
+    )
+}
+
+fn generate_board(config: &GameConfig) -> Result<Board, GlacierError> {
+    // Debug mode logs its own progress, which a redrawn line would garble.
+    if config.debug {
+        return Board::generate_solvable_board(config, None);
+    }
+
+    let mut stdout = io::stdout();
+    let board =
+        Board::generate_solvable_board_with_progress(config, None, &AtomicBool::new(false), |p| {
+            // A line that wraps can no longer be redrawn in place, so cut it to fit the terminal,
+            // leaving the last column free.
+            let width = terminal::size().map_or(80, |(cols, _)| usize::from(cols));
+            let line: String = progress_line(p, config.minimum_moves_required)
+                .chars()
+                .take(width.saturating_sub(1))
+                .collect();
+            let _ = execute!(
+                stdout,
+                Clear(ClearType::CurrentLine),
+                Print('\r'),
+                Print(line)
+            );
+        });
+
+    // Clear the progress line, whether or not a level was found.
+    let _ = execute!(stdout, Clear(ClearType::CurrentLine), Print('\r'));
+    board
+}
+
+fn play_next_input_handler(play_again_signal: &mut bool, quit_signal: &mut bool) -> io::Result<()> {
     let mut event_handler = |event: Event| {
         if let Key(KeyEvent {
             code,
@@ -37,47 +91,88 @@ fn play_next_input_handler(play_again_signal: &mut bool) -> Result<(), Box<dyn E
         }) = event
         {
             match (code, modifiers) {
-                (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => exit_game(),
-                (KeyCode::Char('Q'), _) => exit_game(),
+                (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => *quit_signal = true,
+                (KeyCode::Char('Q'), _) => *quit_signal = true,
                 (KeyCode::Char(' '), _) => *play_again_signal = true,
                 _ => {}
             }
         }
     };
 
-    respond_to_input(&mut event_handler)?;
-    Ok(())
+    respond_to_input(&mut event_handler)
+}
+
+// Play levels until the player quits (with 'Q' or Ctrl-C), which returns Ok.
+pub fn start_game(game_state: GameState) -> Result<(), GlacierError> {
+    let result = play_levels(game_state);
+    // The game can end inside a level, while raw mode is still on. Restore the terminal so the
+    // caller (and any error) prints normally.
+    let _ = disable_raw_mode();
+    result
 }
 
-pub fn start_game(mut game_state: GameState) -> Result<(), Box<dyn Error>> {
+fn play_levels(mut game_state: GameState) -> Result<(), GlacierError> {
     loop {
         // During puzzle generation, disable raw mode so printing works normally.
-        disable_raw_mode()?;
-        clear_terminal()?;
+        disable_raw_mode().map_err(GlacierError::Terminal)?;
+        clear_terminal().map_err(GlacierError::Terminal)?;
 
         for line in get_introduction_section() {
             println!("{line}");
         }
+        if let Some(notice) = game_state.notice.take() {
+            println!("{notice}\n");
+        }
 
-        // Generate a new solvable board with the current config.
-        let mut board = Board::generate_solvable_board(&game_state.config, None)?;
+        // Load the requested level, or generate a new solvable board with the current config.
+        let mut board = match &game_state.board_file {
+            Some(path) => {
+                let mut board = Board::from_json_file(path)?;
+                board.solution = Some(board.solve());
+                board
+            }
+            None => generate_board(&game_state.config)?,
+        };
 
         // Run the main interactive loop for this board.
+        let started = Instant::now();
         {
             let mut renderer = Renderer::new(&mut board, &mut game_state, 50);
-            renderer.render_scene()?;
+            if renderer.render_scene()? == SceneEnd::Quit {
+                return Ok(());
+            }
         }
 
         // After the level finishes, update game_state.
         if board.player_has_won {
             game_state.levels_solved += 1;
+
+            // Levels finished after looking at the solution do not earn stars.
+            if let Some(score) = board.score().filter(|_| !game_state.display_solution) {
+                game_state.total_stars += u32::from(score.stars);
+                if score.is_optimal() {
+                    game_state.optimal_levels += 1;
+                }
+            }
+
+            // Give-ups were already recorded when the solution was shown.
+            if !game_state.display_solution {
+                let score = board.score();
+                let elapsed = started.elapsed();
+                game_state.update_profile(|profile, difficulty| {
+                    profile.record_solve(difficulty, score.as_ref(), elapsed)
+                })?;
+            }
         }
         game_state.display_solution = false;
 
         // Ask whether to play another level.
-        let mut play_next = false;
+        let (mut play_next, mut quit) = (false, false);
         while !play_next {
-            play_next_input_handler(&mut play_next)?;
+            play_next_input_handler(&mut play_next, &mut quit).map_err(GlacierError::Terminal)?;
+            if quit {
+                return Ok(());
+            }
         }
     }
 }
@@ -1,4 +1,72 @@
-use crate::parser::LevelArgs;
+use std::{fmt::Display, ops::RangeInclusive, path::PathBuf, time::Duration};
+
+use crate::error::GlacierError;
+use crate::profile::Profile;
+
+// Bounds on custom level parameters, shared by the CLI and the HTTP server.
+pub const DIMENSION_BOUNDS: RangeInclusive<u8> = 3..=20;
+pub const MOVES_REQUIRED_BOUNDS: RangeInclusive<u16> = 1..=35;
+pub const ROCK_PERCENTAGE_BOUNDS: RangeInclusive<u8> = 5..=50;
+
+// Random boards tried before generation gives up, unless the config says otherwise.
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 1_000_000;
+
+fn check_bounds<T: PartialOrd + Display>(
+    name: &str,
+    value: Option<T>,
+    bounds: &RangeInclusive<T>,
+) -> Result<(), GlacierError> {
+    match value {
+        Some(v) if !bounds.contains(&v) => Err(GlacierError::InvalidConfig(format!(
+            "{} must be between {} and {}, but was {}.",
+            name,
+            bounds.start(),
+            bounds.end(),
+            v
+        ))),
+        _ => Ok(()),
+    }
+}
+
+// Optional changes to a base config. Columns and rows count the playable tiles only;
+// the border is added on top of them.
+#[derive(Clone, Debug, Default)]
+pub struct LevelOverrides {
+    pub columns: Option<u8>,
+    pub rows: Option<u8>,
+    pub moves_required: Option<u16>,
+    pub max_moves: Option<u16>,
+    // Sets both the minimum and maximum number of moves.
+    pub exact_moves: Option<u16>,
+    pub rock_percentage: Option<u8>,
+    pub seed: Option<u64>,
+}
+
+// How candidate boards are built before they are checked with the solver.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum GenerationStrategy {
+    // Scatter rocks at random.
+    #[default]
+    Random,
+    // Plan a path of the required length backwards from the end, placing the rocks it needs.
+    Backward,
+    // Start from random rocks and keep moving them one at a time while the level gets harder.
+    HillClimb,
+}
+
+impl GenerationStrategy {
+    pub fn from_name(name: &str) -> Result<Self, GlacierError> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "random" => Ok(GenerationStrategy::Random),
+            "backward" => Ok(GenerationStrategy::Backward),
+            "hill-climb" => Ok(GenerationStrategy::HillClimb),
+            other => Err(GlacierError::InvalidConfig(format!(
+                "Unknown generation strategy '{}'. Expected random, backward or hill-climb.",
+                other
+            ))),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
 pub struct GameConfig {
@@ -6,6 +74,21 @@ pub struct GameConfig {
     pub rows: u8,
     pub rock_probability: u8, // as a percentage
     pub minimum_moves_required: u16,
+    // Generated levels must not need more moves than this. Setting it to minimum_moves_required
+    // asks for levels that need exactly that many moves.
+    pub maximum_moves: Option<u16>,
+    pub seed: Option<u64>,
+    // Worker threads used to generate boards. The server runs one generation per request in
+    // parallel instead, so it leaves this at 1.
+    pub threads: usize,
+    // Generation gives up after this many boards, or once the time budget (if any) has passed.
+    pub max_attempts: u32,
+    pub time_budget: Option<Duration>,
+    pub strategy: GenerationStrategy,
+    // Generated levels must also reach this difficulty score (see DifficultyMetrics).
+    pub min_difficulty_score: Option<u32>,
+    // Generated levels must have exactly one shortest solution.
+    pub unique_solution: bool,
     pub debug: bool,
     pub board_only: bool,
 }
@@ -17,6 +100,14 @@ impl Default for GameConfig {
             rows: 7,
             minimum_moves_required: 7,
             rock_probability: 15,
+            maximum_moves: None,
+            seed: None,
+            threads: 1,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            time_budget: None,
+            strategy: GenerationStrategy::Random,
+            min_difficulty_score: None,
+            unique_solution: false,
             debug: false,
             board_only: false,
         }
@@ -30,74 +121,247 @@ impl GameConfig {
             rows: size,
             minimum_moves_required,
             rock_probability,
+            maximum_moves: None,
+            seed: None,
+            threads: 1,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            time_budget: None,
+            strategy: GenerationStrategy::Random,
+            min_difficulty_score: None,
+            unique_solution: false,
             debug: false,
             board_only: false,
         }
     }
 
-    pub fn get_config_from_difficulty(difficulty: &str) -> Result<Self, String> {
+    pub fn get_config_from_difficulty(difficulty: &str) -> Result<Self, GlacierError> {
         match difficulty.trim().to_ascii_lowercase().as_str() {
             "easy" => Ok(GameConfig::new_square(7, 7, 15)),
             "medium" => Ok(GameConfig::new_square(12, 11, 15)),
             "hard" => Ok(GameConfig::new_square(17, 18, 10)),
             "extreme" => Ok(GameConfig::new_square(20, 25, 12)),
-            other => Err(format!(
+            other => Err(GlacierError::InvalidConfig(format!(
                 "Unknown difficulty '{}'. Expected one of: easy, medium, hard, or extreme.",
                 other
-            )),
+            ))),
         }
     }
 
-    pub fn for_server_from_difficulty(s: &str) -> Result<Self, String> {
+    pub fn for_server_from_difficulty(s: &str) -> Result<Self, GlacierError> {
         GameConfig::get_config_from_difficulty(s).map(|mut cfg| {
-            // In server mode, board_only is true.
-            // Otherwise, the program will exit if a solvable board cannot be found.
+            // In server mode, board_only is true, so boards show 'S' on the start tile.
             cfg.board_only = true;
             cfg
         })
     }
 
-    pub fn from_level_args(level: &LevelArgs, board_only: bool) -> Result<Self, String> {
-        // Base config comes from difficulty, or falls back to default.
-        let base = level
-            .difficulty
-            .as_deref()
-            .map(GameConfig::get_config_from_difficulty)
-            .transpose()?
-            .unwrap_or_default();
+    // Lengths of shortest solution the generator accepts. Without a maximum, the solver does not
+    // look for solutions more than two moves longer than the minimum.
+    pub fn accepted_moves(&self) -> RangeInclusive<u16> {
+        let minimum = self.minimum_moves_required;
+        minimum..=self.maximum_moves.unwrap_or(minimum.saturating_add(2))
+    }
+
+    // Apply overrides on top of this config, rejecting values outside the allowed bounds.
+    pub fn with_overrides(self, overrides: &LevelOverrides) -> Result<Self, GlacierError> {
+        check_bounds("columns", overrides.columns, &DIMENSION_BOUNDS)?;
+        check_bounds("rows", overrides.rows, &DIMENSION_BOUNDS)?;
+        check_bounds(
+            "moves_required",
+            overrides.moves_required,
+            &MOVES_REQUIRED_BOUNDS,
+        )?;
+        check_bounds("max_moves", overrides.max_moves, &MOVES_REQUIRED_BOUNDS)?;
+        check_bounds("exact_moves", overrides.exact_moves, &MOVES_REQUIRED_BOUNDS)?;
+        check_bounds(
+            "rock_percentage",
+            overrides.rock_percentage,
+            &ROCK_PERCENTAGE_BOUNDS,
+        )?;
+
+        let (minimum_moves_required, maximum_moves) = match overrides.exact_moves {
+            Some(_) if overrides.moves_required.is_some() || overrides.max_moves.is_some() => {
+                return Err(GlacierError::InvalidConfig(
+                    "exact_moves cannot be combined with moves_required or max_moves.".to_string(),
+                ));
+            }
+            Some(exact) => (exact, Some(exact)),
+            None => (
+                overrides
+                    .moves_required
+                    .unwrap_or(self.minimum_moves_required),
+                overrides.max_moves.or(self.maximum_moves),
+            ),
+        };
+        if let Some(maximum) = maximum_moves.filter(|&m| m < minimum_moves_required) {
+            return Err(GlacierError::InvalidConfig(format!(
+                "max_moves must be at least the {} moves required, but was {}.",
+                minimum_moves_required, maximum
+            )));
+        }
 
         Ok(GameConfig {
             // Add 2 to the column and row counts to account for borders.
-            cols: level.columns.unwrap_or(base.cols) + 2,
-            rows: level.rows.unwrap_or(base.rows) + 2,
-            rock_probability: level.rock_percentage.unwrap_or(base.rock_probability),
-            minimum_moves_required: level.moves_required.unwrap_or(base.minimum_moves_required),
-            debug: level.debug,
-            board_only,
+            cols: overrides.columns.map_or(self.cols, |c| c + 2),
+            rows: overrides.rows.map_or(self.rows, |r| r + 2),
+            rock_probability: overrides.rock_percentage.unwrap_or(self.rock_probability),
+            minimum_moves_required,
+            maximum_moves,
+            seed: overrides.seed.or(self.seed),
+            ..self
         })
     }
 }
 
 pub struct GameState {
     pub config: GameConfig,
+    // Name the profile files this game's results under, e.g. "easy" or "custom".
+    pub difficulty: String,
+    // When set, every level is loaded from this file instead of being generated.
+    pub board_file: Option<PathBuf>,
     pub levels_solved: u16,
+    // Stars earned across all levels, and how many levels were solved in par.
+    pub total_stars: u32,
+    pub optimal_levels: u16,
     pub player_focused_view: bool,
     pub display_solution: bool,
+    pub trap_warnings: bool,
+    // Saved after every level, when set.
+    pub profile: Option<(PathBuf, Profile)>,
+    // Shown once, under the introduction to the first level (e.g. a problem with the profile).
+    pub notice: Option<String>,
 }
 
 impl GameState {
     pub fn new(config: GameConfig, player_focused_view: bool) -> Self {
         GameState {
             config,
+            difficulty: "default".to_string(),
+            board_file: None,
             levels_solved: 0,
+            total_stars: 0,
+            optimal_levels: 0,
             player_focused_view,
             display_solution: false,
+            trap_warnings: false,
+            profile: None,
+            notice: None,
         }
     }
 
-    pub fn from(level: LevelArgs, board_only: bool) -> Result<Self, String> {
-        let config = GameConfig::from_level_args(&level, board_only)?;
-        let player_focused_view = !level.full_level_view;
-        Ok(GameState::new(config, player_focused_view))
+    // Apply a change to the profile (if any) and save it straight away, so quitting mid-level loses nothing.
+    pub fn update_profile(
+        &mut self,
+        update: impl FnOnce(&mut Profile, &str),
+    ) -> Result<(), GlacierError> {
+        match &mut self.profile {
+            Some((path, profile)) => {
+                update(profile, &self.difficulty);
+                profile.save(path).map_err(|e| {
+                    GlacierError::io(format!("Could not save profile '{}'", path.display()), e)
+                })
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_overrides_adds_border_to_dimensions() {
+        let overrides = LevelOverrides {
+            columns: Some(10),
+            rows: Some(4),
+            moves_required: Some(3),
+            max_moves: Some(5),
+            exact_moves: None,
+            rock_percentage: Some(20),
+            seed: Some(9),
+        };
+        let config = GameConfig::default().with_overrides(&overrides).unwrap();
+
+        assert_eq!(config.cols, 12);
+        assert_eq!(config.rows, 6);
+        assert_eq!(config.minimum_moves_required, 3);
+        assert_eq!(config.maximum_moves, Some(5));
+        assert_eq!(config.accepted_moves(), 3..=5);
+        assert_eq!(config.rock_probability, 20);
+        assert_eq!(config.seed, Some(9));
+    }
+
+    #[test]
+    fn with_overrides_keeps_base_values_when_unset() {
+        let base = GameConfig::get_config_from_difficulty("hard").unwrap();
+        let config = base
+            .clone()
+            .with_overrides(&LevelOverrides::default())
+            .unwrap();
+
+        assert_eq!(config.cols, base.cols);
+        assert_eq!(config.rows, base.rows);
+        assert_eq!(config.minimum_moves_required, base.minimum_moves_required);
+        assert_eq!(config.rock_probability, base.rock_probability);
+    }
+
+    #[test]
+    fn with_overrides_rejects_out_of_range_values() {
+        let overrides = LevelOverrides {
+            columns: Some(21),
+            ..LevelOverrides::default()
+        };
+        let err = GameConfig::default()
+            .with_overrides(&overrides)
+            .unwrap_err();
+        assert!(matches!(err, GlacierError::InvalidConfig(_)));
+        assert_eq!(
+            err.to_string(),
+            "columns must be between 3 and 20, but was 21."
+        );
+
+        let overrides = LevelOverrides {
+            rock_percentage: Some(4),
+            ..LevelOverrides::default()
+        };
+        assert!(GameConfig::default().with_overrides(&overrides).is_err());
+
+        let overrides = LevelOverrides {
+            moves_required: Some(0),
+            ..LevelOverrides::default()
+        };
+        assert!(GameConfig::default().with_overrides(&overrides).is_err());
+    }
+
+    #[test]
+    fn with_overrides_limits_moves() {
+        let base = GameConfig::get_config_from_difficulty("easy").unwrap();
+        assert_eq!(base.accepted_moves(), 7..=9);
+
+        let overrides = LevelOverrides {
+            exact_moves: Some(9),
+            ..LevelOverrides::default()
+        };
+        let config = base.clone().with_overrides(&overrides).unwrap();
+        assert_eq!(config.accepted_moves(), 9..=9);
+
+        let overrides = LevelOverrides {
+            exact_moves: Some(9),
+            moves_required: Some(8),
+            ..LevelOverrides::default()
+        };
+        assert!(base.clone().with_overrides(&overrides).is_err());
+
+        // The difficulty's minimum still applies when only a maximum is given.
+        let overrides = LevelOverrides {
+            max_moves: Some(5),
+            ..LevelOverrides::default()
+        };
+        let err = base.with_overrides(&overrides).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "max_moves must be at least the 7 moves required, but was 5."
+        );
     }
 }
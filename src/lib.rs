@@ -0,0 +1,22 @@
+//! Level generation, solving and move simulation for Glacier Slide.
+//!
+//! The terminal game is behind the `tui` feature, and the HTTP server behind the `server` feature.
+
+pub mod board;
+pub mod error;
+pub mod game_state;
+pub mod profile;
+
+#[cfg(feature = "tui")]
+pub mod game;
+#[cfg(feature = "tui")]
+mod renderer;
+#[cfg(feature = "tui")]
+mod system;
+
+#[cfg(feature = "server")]
+pub mod web_server;
+
+pub use board::{Board, BoardLayout, Direction, LevelScore, Point, Replay, Solution};
+pub use error::GlacierError;
+pub use game_state::{GameConfig, LevelOverrides};
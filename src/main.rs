@@ -1,37 +1,179 @@
-mod board;
-mod web_server;
-
-mod game;
-mod game_state;
 mod parser;
-mod renderer;
-mod system;
 
-use std::error::Error;
+use std::{
+    fs,
+    io::{self, Read},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    thread,
+    time::Duration,
+};
 
 use clap::Parser;
-use game_state::GameState;
-use parser::{Args, Command, ServeArgs};
+use glacier_slide::{board::Board, game, profile::Profile, web_server, GlacierError};
+use parser::{Args, Command, ServeArgs, SolveArgs, StatsArgs};
+
+fn read_board_text(path: Option<&Path>) -> Result<String, GlacierError> {
+    match path {
+        Some(p) if p != Path::new("-") => fs::read_to_string(p).map_err(|e| {
+            GlacierError::io(format!("Could not read board file '{}'", p.display()), e)
+        }),
+        _ => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| GlacierError::io("Could not read board from STDIN", e))?;
+            Ok(text)
+        }
+    }
+}
+
+// The given profile path, or the default one in the user's data directory.
+fn profile_path(path: Option<PathBuf>) -> Option<PathBuf> {
+    path.or_else(Profile::default_path)
+}
+
+fn load_profile(path: &Path) -> Result<Profile, GlacierError> {
+    Profile::load(path)
+        .map_err(|e| GlacierError::io(format!("Could not read profile '{}'", path.display()), e))
+}
+
+fn print_stats(profile: &Profile) {
+    if profile.difficulties.is_empty() {
+        println!("No levels played yet.");
+        return;
+    }
+
+    println!(
+        "{:<10} {:>6} {:>8} {:>15} {:>9} {:>6} {:>11}",
+        "Difficulty", "Solved", "Give-ups", "Best efficiency", "Avg. time", "Streak", "Best streak"
+    );
+    for (difficulty, stats) in &profile.difficulties {
+        let efficiency = stats
+            .best_efficiency
+            .map_or("-".to_string(), |e| format!("{}%", e));
+        let average = stats
+            .average_solve_time()
+            .map_or("-".to_string(), |t| format!("{:.1}s", t.as_secs_f64()));
+
+        println!(
+            "{:<10} {:>6} {:>8} {:>15} {:>9} {:>6} {:>11}",
+            difficulty,
+            stats.levels_solved,
+            stats.give_ups,
+            efficiency,
+            average,
+            stats.current_streak,
+            stats.best_streak
+        );
+    }
+}
 
 #[tokio::main]
-async fn main() -> Result<(), Box<dyn Error>> {
-    tracing_subscriber::fmt::init();
+async fn main() -> ExitCode {
+    // Log to STDERR so JSON printed to STDOUT can be piped into files or other commands.
+    tracing_subscriber::fmt().with_writer(io::stderr).init();
     let cli = Args::parse();
 
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            ExitCode::from(e.exit_code())
+        }
+    }
+}
+
+async fn run(cli: Args) -> Result<(), GlacierError> {
     match cli.command {
-        Command::Serve(ServeArgs { bind }) => {
-            web_server::start_web_server(bind).await?;
+        Command::Serve(ServeArgs {
+            bind,
+            generation_timeout,
+            max_concurrent_generations,
+            pool_size,
+        }) => {
+            let max_concurrent_generations = match max_concurrent_generations {
+                Some(count) => count as usize,
+                None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            };
+            web_server::start_web_server(web_server::ServerConfig {
+                bind,
+                generation_timeout: Duration::from_secs(generation_timeout),
+                max_concurrent_generations,
+                pool_size,
+            })
+            .await?;
         }
-        Command::Play(level_args) => {
-            let game_state = GameState::from(level_args, false)?;
+        Command::Play(mut play_args) => {
+            let path = profile_path(play_args.profile.take());
+            let mut game_state = play_args.into_game_state()?;
+            // Without a data directory, or a readable profile, the game is still playable, just not
+            // recorded.
+            if let Some(path) = path {
+                match Profile::load_or_back_up(&path) {
+                    Ok((profile, backup)) => {
+                        game_state.notice = backup.map(|backup| {
+                            format!(
+                                "Warning: profile '{}' was corrupt, so it was moved to '{}' and a new profile started.",
+                                path.display(),
+                                backup.display()
+                            )
+                        });
+                        game_state.profile = Some((path, profile));
+                    }
+                    Err(e) => {
+                        game_state.notice = Some(format!(
+                            "Warning: could not read profile '{}' ({}), so results will not be recorded.",
+                            path.display(),
+                            e
+                        ));
+                    }
+                }
+            }
             game::start_game(game_state)?;
         }
         Command::Generate(level_args) => {
-            let game_state = GameState::from(level_args, true)?;
+            let game_state = level_args.into_game_state(true)?;
             // Do not play the game, just return the puzzle to STDOUT as JSON.
-            let board = board::Board::generate_solvable_board(&game_state.config, None)?;
+            let board = Board::generate_solvable_board(&game_state.config, None)?;
             print!("{}", board.get_layout_json());
         }
+        Command::Solve(SolveArgs { board_file }) => {
+            let text = read_board_text(board_file.as_deref())?;
+            let board = Board::from_text(&text)?;
+            let solution = board.solve();
+
+            match solution.get_solution_string() {
+                Some(steps) => {
+                    println!("Solution: {}", steps);
+                    println!("Moves: {}", steps.len());
+                }
+                None => println!("The board is unsolvable."),
+            }
+            println!("Edges traversed: {}", solution.edges_traversed);
+            if let Some(d) = board.difficulty_metrics() {
+                println!(
+                    "Difficulty score: {} ({} reachable positions, {:.2} slides per position, {} traps, {} decoys, {} optimal solutions)",
+                    d.score,
+                    d.reachable_states,
+                    d.branching_factor,
+                    d.trap_states,
+                    d.decoy_states,
+                    d.optimal_solutions
+                );
+            }
+        }
+        Command::Stats(StatsArgs { profile }) => {
+            let path = profile_path(profile).ok_or_else(|| {
+                GlacierError::InvalidConfig(
+                    "Could not find a data directory. Pass the profile with --profile.".to_string(),
+                )
+            })?;
+            println!("Profile: {}", path.display());
+            println!();
+            print_stats(&load_profile(&path)?);
+        }
     }
     Ok(())
 }
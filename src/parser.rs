@@ -1,16 +1,34 @@
-use clap::{arg, command, Parser, Subcommand};
+use std::{num::NonZeroUsize, path::PathBuf, thread, time::Duration};
+
+use clap::{Parser, Subcommand};
 use clap_num::number_range;
 
+use glacier_slide::{
+    game_state::{
+        GameConfig, GameState, GenerationStrategy, LevelOverrides, DIMENSION_BOUNDS,
+        MOVES_REQUIRED_BOUNDS, ROCK_PERCENTAGE_BOUNDS,
+    },
+    GlacierError,
+};
+
 fn dimension_bounds(s: &str) -> Result<u8, String> {
-    number_range(s, 3, 20)
+    number_range(s, *DIMENSION_BOUNDS.start(), *DIMENSION_BOUNDS.end())
 }
 
 fn moves_required(s: &str) -> Result<u16, String> {
-    number_range(s, 1, 35)
+    number_range(
+        s,
+        *MOVES_REQUIRED_BOUNDS.start(),
+        *MOVES_REQUIRED_BOUNDS.end(),
+    )
 }
 
 fn rock_percentage(s: &str) -> Result<u8, String> {
-    number_range(s, 5, 50)
+    number_range(
+        s,
+        *ROCK_PERCENTAGE_BOUNDS.start(),
+        *ROCK_PERCENTAGE_BOUNDS.end(),
+    )
 }
 
 #[derive(Parser, Debug)]
@@ -23,11 +41,15 @@ pub struct Args {
 #[derive(Subcommand, Debug)]
 pub enum Command {
     /// Play the game in the terminal.
-    Play(LevelArgs),
+    Play(PlayArgs),
     /// Generate a solvable board and print it as JSON.
     Generate(LevelArgs),
+    /// Solve a board (JSON or ASCII grid) and print the shortest solution.
+    Solve(SolveArgs),
     /// Run the HTTP server to generate solvable boards.
     Serve(ServeArgs),
+    /// Print the statistics recorded in the player profile.
+    Stats(StatsArgs),
 }
 
 #[derive(Debug, Parser)]
@@ -44,9 +66,41 @@ pub struct LevelArgs {
     /// Minimum moves required to win
     #[arg(short, long, value_parser = moves_required, value_name = "MOVES")]
     pub moves_required: Option<u16>,
+    /// Maximum moves required to win
+    #[arg(long, value_parser = moves_required, value_name = "MOVES")]
+    pub max_moves: Option<u16>,
+    /// Exact number of moves required to win
+    #[arg(
+        long,
+        value_parser = moves_required,
+        value_name = "MOVES",
+        conflicts_with_all = ["moves_required", "max_moves"]
+    )]
+    pub exact_moves: Option<u16>,
     /// Percent of tiles that are rocks
     #[arg(short = 'p', long, value_parser = rock_percentage, value_name = "PERCENTAGE")]
     pub rock_percentage: Option<u8>,
+    /// Seed for the level generator (the same seed and options reproduce the same level)
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Threads used to generate levels, at most 256 [default: number of CPUs]
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..=256))]
+    pub threads: Option<u64>,
+    /// How candidate levels are built: random rocks, a path planned backwards from the end, or rocks moved one at a time until the level is hard enough
+    #[arg(long, ignore_case = true, value_parser = ["random", "backward", "hill-climb"], default_value = "random")]
+    pub strategy: String,
+    /// Only accept levels with at least this difficulty score (as printed by `solve`)
+    #[arg(long, value_name = "SCORE")]
+    pub min_difficulty_score: Option<u32>,
+    /// Only accept levels with exactly one shortest solution
+    #[arg(long)]
+    pub unique_solution: bool,
+    /// Random boards to try before giving up on generating a level [default: 1000000]
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_attempts: Option<u32>,
+    /// Seconds to spend generating a level before giving up [default: no limit]
+    #[arg(long, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub time_budget: Option<u64>,
     /// Toggle between views
     #[arg(short = 'v', long)]
     pub full_level_view: bool,
@@ -55,11 +109,146 @@ pub struct LevelArgs {
     pub debug: bool,
 }
 
+impl From<&LevelArgs> for LevelOverrides {
+    fn from(level: &LevelArgs) -> Self {
+        LevelOverrides {
+            columns: level.columns,
+            rows: level.rows,
+            moves_required: level.moves_required,
+            max_moves: level.max_moves,
+            exact_moves: level.exact_moves,
+            rock_percentage: level.rock_percentage,
+            seed: level.seed,
+        }
+    }
+}
+
+impl LevelArgs {
+    // Name the profile records results under. Like the server's metrics, any custom level
+    // parameter makes the level "custom".
+    pub fn difficulty_label(&self) -> String {
+        let is_custom = self.columns.is_some()
+            || self.rows.is_some()
+            || self.moves_required.is_some()
+            || self.max_moves.is_some()
+            || self.exact_moves.is_some()
+            || self.rock_percentage.is_some();
+
+        match self.difficulty.as_deref() {
+            _ if is_custom => "custom".to_string(),
+            Some(difficulty) => difficulty.trim().to_ascii_lowercase(),
+            None => "default".to_string(),
+        }
+    }
+
+    pub fn game_config(&self, board_only: bool) -> Result<GameConfig, GlacierError> {
+        // Base config comes from difficulty, or falls back to default.
+        let base = self
+            .difficulty
+            .as_deref()
+            .map(GameConfig::get_config_from_difficulty)
+            .transpose()?
+            .unwrap_or_default();
+
+        GameConfig {
+            // Add 2 to the column and row counts to account for borders.
+            cols: base.cols + 2,
+            rows: base.rows + 2,
+            threads: match self.threads {
+                Some(count) => count as usize,
+                None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            },
+            max_attempts: self.max_attempts.unwrap_or(base.max_attempts),
+            strategy: GenerationStrategy::from_name(&self.strategy)?,
+            min_difficulty_score: self.min_difficulty_score.or(base.min_difficulty_score),
+            unique_solution: self.unique_solution || base.unique_solution,
+            time_budget: self
+                .time_budget
+                .map(Duration::from_secs)
+                .or(base.time_budget),
+            debug: self.debug,
+            board_only,
+            ..base
+        }
+        .with_overrides(&LevelOverrides::from(self))
+    }
+
+    pub fn into_game_state(self, board_only: bool) -> Result<GameState, GlacierError> {
+        let config = self.game_config(board_only)?;
+        let mut game_state = GameState::new(config, !self.full_level_view);
+        game_state.difficulty = self.difficulty_label();
+        Ok(game_state)
+    }
+}
+
+// Options that only make sense when playing, on top of the level options shared with `generate`.
+#[derive(Debug, Parser)]
+pub struct PlayArgs {
+    #[command(flatten)]
+    pub level: LevelArgs,
+    /// Play the level stored in a board JSON file (as printed by `generate`)
+    #[arg(
+        short = 'f',
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["difficulty", "columns", "rows", "moves_required", "max_moves", "exact_moves", "rock_percentage", "seed"]
+    )]
+    pub board_file: Option<PathBuf>,
+    /// Player profile to record results in [default: profile.json in the user's data directory]
+    #[arg(long, value_name = "PATH")]
+    pub profile: Option<PathBuf>,
+    /// Warn when the player slides somewhere the end can no longer be reached from
+    #[arg(long)]
+    pub trap_warnings: bool,
+}
+
+impl PlayArgs {
+    // A level loaded from a file is always "custom".
+    pub fn difficulty_label(&self) -> String {
+        match self.board_file {
+            Some(_) => "custom".to_string(),
+            None => self.level.difficulty_label(),
+        }
+    }
+
+    pub fn into_game_state(self) -> Result<GameState, GlacierError> {
+        let difficulty = self.difficulty_label();
+        let mut game_state = self.level.into_game_state(false)?;
+        game_state.difficulty = difficulty;
+        game_state.trap_warnings = self.trap_warnings;
+        game_state.board_file = self.board_file;
+        Ok(game_state)
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct SolveArgs {
+    /// Board file as JSON (as printed by `generate`) or an ASCII grid. Reads STDIN if omitted or '-'
+    #[arg(value_name = "PATH")]
+    pub board_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub struct StatsArgs {
+    /// Player profile to read [default: profile.json in the user's data directory]
+    #[arg(long, value_name = "PATH")]
+    pub profile: Option<PathBuf>,
+}
+
 #[derive(Debug, Parser)]
 pub struct ServeArgs {
     /// The address and port the server will bind to
     #[arg(long, default_value = "127.0.0.1:7878")]
     pub bind: String,
+    /// Seconds a /board request may take before it is abandoned
+    #[arg(long, default_value_t = 30, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub generation_timeout: u64,
+    /// Maximum number of boards generated at once [default: number of CPUs]
+    #[arg(long, value_name = "COUNT", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_concurrent_generations: Option<u64>,
+    /// Number of ready boards kept for each difficulty (0 disables the pool)
+    #[arg(long, default_value_t = 3, value_name = "COUNT")]
+    pub pool_size: usize,
 }
 
 #[cfg(test)]
@@ -117,12 +306,13 @@ mod tests {
         let args = Args::parse_from(["./program", "play"]);
 
         match args.command {
-            Command::Play(level) => {
+            Command::Play(PlayArgs { level, .. }) => {
                 assert!(level.difficulty.is_none());
                 assert!(level.columns.is_none());
                 assert!(level.rows.is_none());
                 assert!(level.moves_required.is_none());
                 assert!(level.rock_percentage.is_none());
+                assert!(level.seed.is_none());
                 assert!(!level.full_level_view);
                 assert!(!level.debug);
             }
@@ -136,7 +326,7 @@ mod tests {
         let args = Args::parse_from(["./program", "play", diff_str]);
 
         match args.command {
-            Command::Play(level) => {
+            Command::Play(PlayArgs { level, .. }) => {
                 assert_eq!(level.difficulty.as_deref(), Some(diff_str));
             }
             _ => panic!("Expected Play command"),
@@ -149,7 +339,7 @@ mod tests {
         let args = Args::parse_from(["./program", "play", diff_str]);
 
         match args.command {
-            Command::Play(level) => {
+            Command::Play(PlayArgs { level, .. }) => {
                 assert_eq!(level.difficulty.as_deref(), Some(diff_str));
             }
             _ => panic!("Expected Play command"),
@@ -178,11 +368,14 @@ mod tests {
             "--rock-percentage",
             "30",
             "-v",
+            "--trap-warnings",
             "-d",
         ]);
 
         match args.command {
-            Command::Play(level) => {
+            Command::Play(play) => {
+                assert!(play.trap_warnings);
+                let level = play.level;
                 assert_eq!(level.difficulty.as_deref(), Some("easy"));
                 assert_eq!(level.columns, Some(10));
                 assert_eq!(level.rows, Some(12));
@@ -195,6 +388,117 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_threads_defaults_to_available_cores() {
+        let args = Args::parse_from(["./program", "generate", "easy", "--threads", "3"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(level.game_config(true).unwrap().threads, 3);
+
+        let args = Args::parse_from(["./program", "generate", "easy"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert!(level.game_config(true).unwrap().threads >= 1);
+
+        assert!(Args::try_parse_from(["./program", "generate", "--threads", "0"]).is_err());
+        assert!(Args::try_parse_from(["./program", "generate", "--threads", "257"]).is_err());
+    }
+
+    #[test]
+    fn parse_generation_budgets() {
+        let args = Args::parse_from([
+            "./program",
+            "generate",
+            "--max-attempts",
+            "5000",
+            "--time-budget",
+            "3",
+        ]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        let config = level.game_config(true).unwrap();
+        assert_eq!(config.max_attempts, 5000);
+        assert_eq!(config.time_budget, Some(Duration::from_secs(3)));
+
+        let args = Args::parse_from(["./program", "generate"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        let config = level.game_config(true).unwrap();
+        assert_eq!(config.max_attempts, 1_000_000);
+        assert_eq!(config.time_budget, None);
+    }
+
+    #[test]
+    fn parse_max_and_exact_moves() {
+        let args = Args::parse_from(["./program", "generate", "easy", "--max-moves", "8"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(level.difficulty_label(), "custom");
+        assert_eq!(level.game_config(true).unwrap().accepted_moves(), 7..=8);
+
+        let args = Args::parse_from(["./program", "generate", "--exact-moves", "12"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(level.game_config(true).unwrap().accepted_moves(), 12..=12);
+
+        let result =
+            Args::try_parse_from(["./program", "generate", "-m", "5", "--exact-moves", "6"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn parse_unique_solution() {
+        let args = Args::parse_from(["./program", "generate", "--unique-solution"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert!(level.game_config(true).unwrap().unique_solution);
+
+        let args = Args::parse_from(["./program", "generate"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert!(!level.game_config(true).unwrap().unique_solution);
+    }
+
+    #[test]
+    fn parse_strategy() {
+        let args = Args::parse_from(["./program", "generate", "--strategy", "Backward"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(
+            level.game_config(true).unwrap().strategy,
+            GenerationStrategy::Backward
+        );
+
+        let args = Args::parse_from(["./program", "generate"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(
+            level.game_config(true).unwrap().strategy,
+            GenerationStrategy::Random
+        );
+
+        let args = Args::parse_from(["./program", "generate", "--strategy", "hill-climb"]);
+        let Command::Generate(level) = args.command else {
+            panic!("Expected Generate command");
+        };
+        assert_eq!(
+            level.game_config(true).unwrap().strategy,
+            GenerationStrategy::HillClimb
+        );
+
+        assert!(Args::try_parse_from(["./program", "generate", "--strategy", "sideways"]).is_err());
+    }
+
     #[test]
     fn parse_play_rejects_out_of_range_rock_percentage() {
         // Below minimum (5)
@@ -206,6 +510,40 @@ mod tests {
         assert!(res.is_err());
     }
 
+    #[test]
+    fn parse_play_with_board_file() {
+        let args = Args::parse_from(["./program", "play", "--board-file", "level.json"]);
+
+        match args.command {
+            Command::Play(play) => {
+                assert_eq!(play.board_file, Some(PathBuf::from("level.json")));
+            }
+            _ => panic!("Expected Play command"),
+        }
+    }
+
+    #[test]
+    fn play_only_options_are_rejected_by_generate() {
+        for option in [
+            &["--board-file", "level.json"][..],
+            &["--profile", "me.json"],
+            &["--trap-warnings"],
+        ] {
+            let mut args = vec!["./program", "generate"];
+            args.extend(option);
+            assert!(Args::try_parse_from(args).is_err(), "{option:?}");
+        }
+    }
+
+    #[test]
+    fn parse_play_rejects_board_file_with_level_options() {
+        let res = Args::try_parse_from(["./program", "play", "hard", "--board-file", "level.json"]);
+        assert!(res.is_err());
+
+        let res = Args::try_parse_from(["./program", "play", "-f", "level.json", "--rows", "5"]);
+        assert!(res.is_err());
+    }
+
     /////////////////////////
     // Generate subcommand //
     /////////////////////////
@@ -237,6 +575,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn parse_generate_with_seed() {
+        let args = Args::parse_from(["./program", "generate", "easy", "--seed", "12345"]);
+
+        match args.command {
+            Command::Generate(level) => {
+                assert_eq!(level.seed, Some(12345));
+            }
+            _ => panic!("Expected Generate command"),
+        }
+    }
+
+    #[test]
+    fn parse_generate_rejects_invalid_seed() {
+        let res = Args::try_parse_from(["./program", "generate", "--seed", "-1"]);
+        assert!(res.is_err());
+    }
+
     #[test]
     fn parse_generate_rejects_out_of_range_rock_percentage() {
         // Below minimum (5)
@@ -249,6 +605,31 @@ mod tests {
         assert!(res.is_err());
     }
 
+    //////////////////////
+    // Solve subcommand //
+    //////////////////////
+    #[test]
+    fn parse_solve_reads_stdin_by_default() {
+        let args = Args::parse_from(["./program", "solve"]);
+
+        match args.command {
+            Command::Solve(solve_args) => assert!(solve_args.board_file.is_none()),
+            _ => panic!("Expected Solve command"),
+        }
+    }
+
+    #[test]
+    fn parse_solve_with_board_file() {
+        let args = Args::parse_from(["./program", "solve", "level.txt"]);
+
+        match args.command {
+            Command::Solve(solve_args) => {
+                assert_eq!(solve_args.board_file, Some(PathBuf::from("level.txt")));
+            }
+            _ => panic!("Expected Solve command"),
+        }
+    }
+
     //////////////////////
     // Serve subcommand //
     //////////////////////
@@ -259,6 +640,9 @@ mod tests {
         match args.command {
             Command::Serve(serve_args) => {
                 assert_eq!(serve_args.bind, "127.0.0.1:7878");
+                assert_eq!(serve_args.generation_timeout, 30);
+                assert!(serve_args.max_concurrent_generations.is_none());
+                assert_eq!(serve_args.pool_size, 3);
             }
             _ => panic!("Expected Serve command"),
         }
@@ -275,4 +659,65 @@ mod tests {
             _ => panic!("Expected Serve command"),
         }
     }
+
+    #[test]
+    fn parse_serve_accepts_generation_limits() {
+        let args = Args::parse_from([
+            "./program",
+            "serve",
+            "--generation-timeout",
+            "5",
+            "--max-concurrent-generations",
+            "3",
+            "--pool-size",
+            "0",
+        ]);
+
+        match args.command {
+            Command::Serve(serve_args) => {
+                assert_eq!(serve_args.generation_timeout, 5);
+                assert_eq!(serve_args.max_concurrent_generations, Some(3));
+                assert_eq!(serve_args.pool_size, 0);
+            }
+            _ => panic!("Expected Serve command"),
+        }
+    }
+
+    #[test]
+    fn parse_serve_rejects_zero_generation_limits() {
+        let res = Args::try_parse_from(["./program", "serve", "--generation-timeout", "0"]);
+        assert!(res.is_err());
+
+        let res = Args::try_parse_from(["./program", "serve", "--max-concurrent-generations", "0"]);
+        assert!(res.is_err());
+    }
+
+    //////////////////////
+    // Stats subcommand //
+    //////////////////////
+    #[test]
+    fn parse_stats_with_profile() {
+        let args = Args::parse_from(["./program", "stats", "--profile", "me.json"]);
+
+        match args.command {
+            Command::Stats(stats_args) => {
+                assert_eq!(stats_args.profile, Some(PathBuf::from("me.json")));
+            }
+            _ => panic!("Expected Stats command"),
+        }
+    }
+
+    #[test]
+    fn difficulty_label_names_custom_levels() {
+        let label = |args: &[&str]| match Args::parse_from(args).command {
+            Command::Play(play) => play.difficulty_label(),
+            _ => panic!("Expected Play command"),
+        };
+
+        assert_eq!(label(&["./program", "play"]), "default");
+        assert_eq!(label(&["./program", "play", "HARD"]), "hard");
+        assert_eq!(label(&["./program", "play", "hard", "--seed", "1"]), "hard");
+        assert_eq!(label(&["./program", "play", "hard", "-r", "5"]), "custom");
+        assert_eq!(label(&["./program", "play", "-f", "level.json"]), "custom");
+    }
 }
@@ -0,0 +1,197 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::LevelScore;
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DifficultyStats {
+    pub levels_solved: u32,
+    pub give_ups: u32,
+    // Best par / moves seen so far, as a percentage (100 means a level was solved in par).
+    pub best_efficiency: Option<u8>,
+    pub total_solve_millis: u64,
+    // Levels solved in a row without giving up.
+    pub current_streak: u32,
+    pub best_streak: u32,
+}
+
+impl DifficultyStats {
+    pub fn average_solve_time(&self) -> Option<Duration> {
+        (self.levels_solved > 0)
+            .then(|| Duration::from_millis(self.total_solve_millis / u64::from(self.levels_solved)))
+    }
+}
+
+// Statistics kept across games, keyed by difficulty ("easy", ..., "custom" or "default").
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub difficulties: BTreeMap<String, DifficultyStats>,
+}
+
+impl Profile {
+    // Where the profile lives unless another path is given, e.g. ~/.local/share/glacier_slide/profile.json.
+    #[cfg(feature = "tui")]
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("glacier_slide").join("profile.json"))
+    }
+
+    // A missing file is an empty profile, so the first game does not need any setup.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Profile::default()),
+            Err(e) => Err(e),
+        }
+    }
+
+    // Like load, but a corrupt profile is moved aside to `<path>.bak` and replaced with an empty
+    // one, so it cannot stop a game from starting. Also returns where the corrupt file went.
+    pub fn load_or_back_up(path: &Path) -> io::Result<(Self, Option<PathBuf>)> {
+        match Profile::load(path) {
+            Ok(profile) => Ok((profile, None)),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                let mut backup = path.as_os_str().to_owned();
+                backup.push(".bak");
+                let backup = PathBuf::from(backup);
+                fs::rename(path, &backup)?;
+                Ok((Profile::default(), Some(backup)))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, json)
+    }
+
+    pub fn record_solve(
+        &mut self,
+        difficulty: &str,
+        score: Option<&LevelScore>,
+        elapsed: Duration,
+    ) {
+        let stats = self.difficulties.entry(difficulty.to_string()).or_default();
+
+        stats.levels_solved += 1;
+        stats.total_solve_millis += elapsed.as_millis() as u64;
+        stats.current_streak += 1;
+        stats.best_streak = stats.best_streak.max(stats.current_streak);
+
+        if let Some(score) = score.filter(|s| s.moves > 0) {
+            let efficiency = (score.par * 100 / score.moves).min(100) as u8;
+            stats.best_efficiency = stats.best_efficiency.max(Some(efficiency));
+        }
+    }
+
+    pub fn record_give_up(&mut self, difficulty: &str) {
+        let stats = self.difficulties.entry(difficulty.to_string()).or_default();
+        stats.give_ups += 1;
+        stats.current_streak = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_solve_tracks_streaks_efficiency_and_time() {
+        let mut profile = Profile::default();
+        profile.record_solve(
+            "easy",
+            Some(&LevelScore::new(10, 7, 0)),
+            Duration::from_secs(20),
+        );
+        profile.record_solve(
+            "easy",
+            Some(&LevelScore::new(7, 7, 0)),
+            Duration::from_secs(10),
+        );
+        profile.record_give_up("easy");
+        profile.record_solve(
+            "easy",
+            Some(&LevelScore::new(8, 7, 0)),
+            Duration::from_secs(30),
+        );
+
+        let stats = &profile.difficulties["easy"];
+        assert_eq!(stats.levels_solved, 3);
+        assert_eq!(stats.give_ups, 1);
+        assert_eq!(stats.best_efficiency, Some(100));
+        assert_eq!(stats.average_solve_time(), Some(Duration::from_secs(20)));
+        assert_eq!(stats.current_streak, 1);
+        assert_eq!(stats.best_streak, 2);
+    }
+
+    #[test]
+    fn average_solve_time_is_none_without_solves() {
+        let mut profile = Profile::default();
+        profile.record_give_up("hard");
+        assert_eq!(profile.difficulties["hard"].average_solve_time(), None);
+    }
+
+    #[test]
+    fn save_and_load_round_trip() {
+        let dir =
+            std::env::temp_dir().join(format!("glacier_slide_profile_{}", std::process::id()));
+        let path = dir.join("nested").join("profile.json");
+
+        // Loading a profile that was never saved gives an empty one.
+        assert_eq!(Profile::load(&path).unwrap(), Profile::default());
+
+        let mut profile = Profile::default();
+        profile.record_solve("medium", None, Duration::from_millis(1500));
+        profile.save(&path).unwrap();
+
+        assert_eq!(Profile::load(&path).unwrap(), profile);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn load_rejects_corrupt_profile() {
+        let path = std::env::temp_dir().join(format!(
+            "glacier_slide_corrupt_profile_{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, "not json").unwrap();
+
+        let err = Profile::load(&path).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn load_or_back_up_moves_corrupt_profile_aside() {
+        let path = std::env::temp_dir().join(format!(
+            "glacier_slide_backed_up_profile_{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, "not json").unwrap();
+
+        let (profile, backup) = Profile::load_or_back_up(&path).unwrap();
+        assert_eq!(profile, Profile::default());
+        let backup = backup.unwrap();
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "not json");
+        assert!(!path.exists());
+
+        // A profile that is fine is loaded as it is.
+        Profile::default().save(&path).unwrap();
+        assert_eq!(Profile::load_or_back_up(&path).unwrap().1, None);
+
+        fs::remove_file(&path).unwrap();
+        fs::remove_file(&backup).unwrap();
+    }
+}
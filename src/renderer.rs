@@ -12,18 +12,38 @@ use crossterm::{
 };
 
 use crate::{
-    board::Board,
+    board::{Board, BoardAnalysis, Direction, Hint},
+    error::GlacierError,
     game::get_introduction_section,
     game_state::GameState,
-    system::{exit_game, respond_to_input},
+    system::respond_to_input,
 };
 
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Up => "up",
+        Direction::Down => "down",
+        Direction::Left => "left",
+        Direction::Right => "right",
+    }
+}
+
+// How the interactive loop for a level ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SceneEnd {
+    Won,
+    Quit,
+}
+
 pub struct Renderer<'a> {
     board: &'a mut Board,
     game_state: &'a mut GameState,
     frame_delay_millis: u64,
     initial_render: bool,
     force_rerender: bool,
+    quit: bool,
+    // Only computed when trap warnings are enabled.
+    analysis: Option<BoardAnalysis>,
 }
 
 impl<'a> Renderer<'a> {
@@ -32,16 +52,19 @@ impl<'a> Renderer<'a> {
         game_state: &'a mut GameState,
         frame_delay_millis: u64,
     ) -> Self {
+        let analysis = game_state.trap_warnings.then(|| board.analyze());
         Self {
             board,
             game_state,
             frame_delay_millis,
             initial_render: false,
             force_rerender: false,
+            quit: false,
+            analysis,
         }
     }
 
-    fn key_input_handler(&mut self, event: Event) {
+    fn key_input_handler(&mut self, event: Event) -> std::result::Result<(), GlacierError> {
         if let Key(KeyEvent {
             code,
             modifiers,
@@ -51,7 +74,7 @@ impl<'a> Renderer<'a> {
         {
             match (code, modifiers) {
                 // Ctrl-C exits immediately
-                (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => exit_game(),
+                (KeyCode::Char('c'), m) if m == KeyModifiers::CONTROL => self.quit = true,
 
                 // View toggle: player-focused vs full-board
                 (KeyCode::Char('v') | KeyCode::Char('V'), _) => {
@@ -61,17 +84,29 @@ impl<'a> Renderer<'a> {
 
                 // Give up: show solution immediately
                 (KeyCode::Char('g') | KeyCode::Char('G'), _) => {
-                    self.game_state.display_solution = true;
+                    if !self.game_state.display_solution {
+                        self.game_state.display_solution = true;
+                        self.game_state.update_profile(|profile, difficulty| {
+                            profile.record_give_up(difficulty)
+                        })?;
+                    }
+                    self.force_rerender = true;
+                }
+
+                // Hint: show only the next optimal slide from the current position
+                (KeyCode::Char('h') | KeyCode::Char('H'), _) => {
+                    self.board.show_hint();
                     self.force_rerender = true;
                 }
 
                 // Game-level exit
-                (KeyCode::Char('Q'), _) => exit_game(),
+                (KeyCode::Char('Q'), _) => self.quit = true,
 
                 // All other keys go to the board movement logic
                 (other, _) => self.board.respond_to_input(other),
             }
         }
+        Ok(())
     }
 
     fn step_animation(&mut self) -> bool {
@@ -88,16 +123,63 @@ impl<'a> Renderer<'a> {
         // Intro
         lines.extend(get_introduction_section());
 
-        // Levels solved
-        lines.push(format!("Levels solved: {}", self.game_state.levels_solved));
+        // Levels solved and score totals
+        lines.push(format!(
+            "Levels solved: {} (in par: {}) | Stars: {}",
+            self.game_state.levels_solved,
+            self.game_state.optimal_levels,
+            self.game_state.total_stars
+        ));
+        lines.push(format!(
+            "Moves: {} | Undos used: {} | Hints used: {}",
+            self.board.moves_made(),
+            self.board.undo_count(),
+            self.board.hints_used()
+        ));
         lines.push(String::new());
 
         // Board view (player-focused or full-board)
         lines.extend(self.board.render_board(self.game_state.player_focused_view));
 
+        // Trap warning, once the player has come to rest
+        let trapped = self.analysis.as_ref().is_some_and(|analysis| {
+            self.board.move_queue.is_empty() && analysis.is_trapped(self.board.player.0)
+        });
+        if trapped {
+            lines.push(
+                "You're trapped: the end can't be reached from here. Press 'Space' to restart."
+                    .to_string(),
+            );
+        }
+
+        // Hint text (the arrow itself is drawn on the board)
+        match self.board.hint() {
+            Some(Hint::Slide(direction)) => {
+                lines.push(format!("Hint: slide {}.", direction_name(direction)))
+            }
+            Some(Hint::DeadEnd) => lines.push(
+                "Hint: the end cannot be reached from here. Undo or press 'Space' to restart."
+                    .to_string(),
+            ),
+            None => {}
+        }
+
         // Win text
         if self.board.player_won() {
-            lines.push("You won! Press 'Space' to play again.".to_string());
+            let result = match self.board.score() {
+                // Looking at the solution forfeits the stars.
+                Some(score) if self.game_state.display_solution => {
+                    format!("You won in {} moves (par {})!", score.moves, score.par)
+                }
+                Some(score) => format!(
+                    "You won in {} moves (par {}): {}",
+                    score.moves,
+                    score.par,
+                    score.star_string()
+                ),
+                None => "You won!".to_string(),
+            };
+            lines.push(format!("{} Press 'Space' to play again.", result));
         }
         lines.push(String::new());
 
@@ -119,6 +201,9 @@ impl<'a> Renderer<'a> {
         if debug {
             let pos = self.board.player.0;
             lines.push(format!("Position: {}, {}", pos.col, pos.row));
+            if let Some(seed) = self.board.seed() {
+                lines.push(format!("Seed: {}", seed));
+            }
             lines.push(format!("Move Queue: {:?}", self.board.move_queue));
 
             let edges_traversed = self
@@ -147,15 +232,21 @@ impl<'a> Renderer<'a> {
         Ok(())
     }
 
-    pub fn render_scene(&mut self) -> Result<()> {
-        enable_raw_mode()?;
+    pub fn render_scene(&mut self) -> std::result::Result<SceneEnd, GlacierError> {
+        enable_raw_mode().map_err(GlacierError::Terminal)?;
         let mut stdout = stdout();
 
         while !self.board.player_has_won {
+            if self.quit {
+                return Ok(SceneEnd::Quit);
+            }
+
             // Handle input (non-blocking, propagates I/O errors)
             {
-                let mut handler = |event| self.key_input_handler(event);
-                respond_to_input(&mut handler)?;
+                let mut handled = Ok(());
+                let mut handler = |event| handled = self.key_input_handler(event);
+                respond_to_input(&mut handler).map_err(GlacierError::Terminal)?;
+                handled?;
             }
 
             // Advance sliding animations (if any)
@@ -166,12 +257,13 @@ impl<'a> Renderer<'a> {
             if needs_rerender {
                 self.force_rerender = false;
                 self.initial_render = true;
-                self.draw_frame(&mut stdout)?;
+                self.draw_frame(&mut stdout)
+                    .map_err(GlacierError::Terminal)?;
             }
 
             thread::sleep(Duration::from_millis(self.frame_delay_millis));
         }
 
-        Ok(())
+        Ok(SceneEnd::Won)
     }
 }
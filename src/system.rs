@@ -4,11 +4,9 @@ use crossterm::{
     cursor::MoveTo,
     event::{poll, read, Event},
     execute,
-    terminal::{disable_raw_mode, Clear, ClearType},
+    terminal::{Clear, ClearType},
 };
 
-use std::process;
-
 // Clear the current screen buffer and move the cursor to (0, 0).
 pub fn clear_terminal() -> io::Result<()> {
     let mut out = stdout();
@@ -17,13 +15,6 @@ pub fn clear_terminal() -> io::Result<()> {
     Ok(())
 }
 
-// This function never returns.
-pub fn exit_game() -> ! {
-    // Best-effort to restore terminal. Ignore errors because we're exiting anyway.
-    let _ = disable_raw_mode();
-    process::exit(130);
-}
-
 // Non-blocking input polling helper that propagates I/O errors.
 pub fn respond_to_input<F: FnMut(Event)>(event_handler: &mut F) -> io::Result<()> {
     if poll(std::time::Duration::from_millis(0))? {
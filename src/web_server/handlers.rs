@@ -1,32 +1,280 @@
-use crate::{board, game_state::GameConfig};
+use crate::{
+    board::{self, BoardLayout, Direction},
+    error::GlacierError,
+    game_state::{GameConfig, LevelOverrides},
+};
 
-use super::metrics::next_request_id;
+use super::{
+    metrics::{metrics, next_request_id},
+    state::AppState,
+};
 
-use axum::{extract::Query, http::StatusCode, response::IntoResponse, Json};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant as StdInstant,
+};
+
+use axum::{
+    extract::{rejection::QueryRejection, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
 use serde::Deserialize;
-use serde_json::json;
+use serde_json::{json, Value};
+use tokio::{
+    task,
+    time::{timeout_at, Instant},
+};
 
-#[derive(Deserialize)]
+// Custom level parameters mirror the CLI's options, and override the difficulty's values.
+#[derive(Default, Deserialize)]
 pub struct BoardRequest {
-    pub difficulty: String,
+    pub difficulty: Option<String>,
+    pub columns: Option<u8>,
+    pub rows: Option<u8>,
+    pub moves_required: Option<u16>,
+    pub max_moves: Option<u16>,
+    pub exact_moves: Option<u16>,
+    pub rock_percentage: Option<u8>,
+    pub seed: Option<u64>,
+}
+
+impl BoardRequest {
+    fn has_custom_parameters(&self) -> bool {
+        self.columns.is_some()
+            || self.rows.is_some()
+            || self.moves_required.is_some()
+            || self.max_moves.is_some()
+            || self.exact_moves.is_some()
+            || self.rock_percentage.is_some()
+    }
+
+    // Requests for a plain difficulty (no custom parameters or seed) can be served from the pool.
+    fn pooled_difficulty(&self) -> Option<&str> {
+        let is_plain = !self.has_custom_parameters() && self.seed.is_none();
+        self.difficulty.as_deref().filter(|_| is_plain)
+    }
+
+    // Label used to group generation metrics.
+    fn metrics_label(&self) -> String {
+        match self.difficulty.as_deref() {
+            _ if self.has_custom_parameters() => "custom".to_string(),
+            Some(difficulty) => difficulty.trim().to_ascii_lowercase(),
+            None => "default".to_string(),
+        }
+    }
+
+    fn to_config(&self) -> Result<GameConfig, GlacierError> {
+        let base = match self.difficulty.as_deref() {
+            Some(difficulty) => GameConfig::for_server_from_difficulty(difficulty)?,
+            None => GameConfig {
+                board_only: true,
+                ..GameConfig::default()
+            },
+        };
+
+        base.with_overrides(&LevelOverrides {
+            columns: self.columns,
+            rows: self.rows,
+            moves_required: self.moves_required,
+            max_moves: self.max_moves,
+            exact_moves: self.exact_moves,
+            rock_percentage: self.rock_percentage,
+            seed: self.seed,
+        })
+    }
+}
+
+// HTTP status for errors coming out of the board and config code.
+fn error_status(err: &GlacierError) -> StatusCode {
+    match err {
+        GlacierError::InvalidConfig(_) | GlacierError::InvalidBoard(_) => StatusCode::BAD_REQUEST,
+        GlacierError::GenerationCancelled => StatusCode::GATEWAY_TIMEOUT,
+        GlacierError::GenerationExhausted { .. }
+        | GlacierError::Io { .. }
+        | GlacierError::Terminal(_) => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+fn error_response(request_id: u64, err: &GlacierError) -> (StatusCode, Json<Value>) {
+    (
+        error_status(err),
+        Json(json!({ "request_id": request_id, "error": err.to_string() })),
+    )
+}
+
+// The board uses the same shape as the "board" field returned by /board.
+#[derive(Deserialize)]
+pub struct SolveRequest {
+    pub board: BoardLayout,
+}
+
+// Moves use the same U/D/L/R alphabet as solution strings.
+#[derive(Deserialize)]
+pub struct ValidateRequest {
+    pub board: BoardLayout,
+    pub moves: String,
+}
+
+pub async fn health_handler(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        Json(json!({ "status": "ok", "pool": state.pool.depths() })),
+    )
+}
+
+// Why work sent to the blocking pool did not produce a result.
+enum BlockingFailure {
+    // No free slot before the deadline.
+    Busy,
+    TimedOut,
+    Panicked(task::JoinError),
+}
+
+// Run CPU-bound work on the blocking pool under one of the generation slots, so it does not stall
+// the async workers. Waiting for a free slot counts against the deadline, and the slot is held
+// until the work actually stops, even if the deadline passes first.
+async fn run_blocking<T: Send + 'static>(
+    state: &AppState,
+    deadline: Instant,
+    work: impl FnOnce() -> T + Send + 'static,
+) -> Result<T, BlockingFailure> {
+    let permit = match timeout_at(deadline, state.generation_slots.clone().acquire_owned()).await {
+        Ok(Ok(permit)) => permit,
+        Ok(Err(_)) | Err(_) => return Err(BlockingFailure::Busy),
+    };
+
+    let task = task::spawn_blocking(move || {
+        let _permit = permit;
+        work()
+    });
+
+    match timeout_at(deadline, task).await {
+        Ok(Ok(value)) => Ok(value),
+        Ok(Err(e)) => Err(BlockingFailure::Panicked(e)),
+        Err(_) => Err(BlockingFailure::TimedOut),
+    }
 }
 
-pub async fn health_handler() -> impl IntoResponse {
-    (StatusCode::OK, Json(json!({ "status": "ok" })))
+// Generate on the blocking pool, so slow levels do not stall the async workers.
+async fn generate_board(
+    state: &AppState,
+    config: GameConfig,
+    request_id: u64,
+    difficulty: &str,
+) -> Result<board::Board, (StatusCode, Json<Value>)> {
+    let deadline = Instant::now() + state.generation_timeout;
+    let error = |status: StatusCode, msg: &str| {
+        (
+            status,
+            Json(json!({ "request_id": request_id, "error": msg })),
+        )
+    };
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let work = {
+        let cancelled = Arc::clone(&cancelled);
+        move || {
+            let started = StdInstant::now();
+            let result = board::Board::generate_solvable_board_cancellable(
+                &config,
+                Some(request_id),
+                &cancelled,
+            );
+            (result, started.elapsed())
+        }
+    };
+
+    match run_blocking(state, deadline, work).await {
+        Ok((Ok(board), elapsed)) => {
+            metrics().record_generation(difficulty, elapsed, &board);
+            Ok(board)
+        }
+        Ok((Err(e), _)) => {
+            tracing::error!(request_id, error = %e, "Failed to generate solvable board");
+            let reason = match e {
+                GlacierError::GenerationCancelled => "cancelled",
+                _ => "exhausted",
+            };
+            metrics().record_generation_failure(difficulty, reason);
+            Err(error_response(request_id, &e))
+        }
+        Err(BlockingFailure::Busy) => {
+            tracing::warn!(request_id, "No free generation slot before the deadline");
+            metrics().record_generation_failure(difficulty, "busy");
+            Err(error(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "server is busy generating other boards",
+            ))
+        }
+        Err(BlockingFailure::Panicked(e)) => {
+            tracing::error!(request_id, error = %e, "Board generation task failed");
+            metrics().record_generation_failure(difficulty, "panic");
+            Err(error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to generate solvable board",
+            ))
+        }
+        Err(BlockingFailure::TimedOut) => {
+            cancelled.store(true, Ordering::Relaxed);
+            tracing::warn!(request_id, "Board generation timed out");
+            metrics().record_generation_failure(difficulty, "timeout");
+            Err(error(
+                StatusCode::GATEWAY_TIMEOUT,
+                "timed out generating board",
+            ))
+        }
+    }
+}
+
+// Response for solving work that never finished. `action` names it in the error, e.g. "solving board".
+fn blocking_failure_response(
+    request_id: u64,
+    failure: BlockingFailure,
+    action: &str,
+) -> (StatusCode, Json<Value>) {
+    let (status, msg) = match failure {
+        BlockingFailure::Busy => {
+            tracing::warn!(request_id, "No free slot before the deadline");
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "server is busy".to_string(),
+            )
+        }
+        BlockingFailure::TimedOut => {
+            tracing::warn!(request_id, "Timed out {}", action);
+            (StatusCode::GATEWAY_TIMEOUT, format!("timed out {}", action))
+        }
+        BlockingFailure::Panicked(e) => {
+            tracing::error!(request_id, error = %e, "Task failed {}", action);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("failed {}", action),
+            )
+        }
+    };
+    (
+        status,
+        Json(json!({ "request_id": request_id, "error": msg })),
+    )
 }
 
-pub async fn board_handler(Query(params): Query<BoardRequest>) -> impl IntoResponse {
+pub async fn board_handler(
+    State(state): State<AppState>,
+    query: Result<Query<BoardRequest>, QueryRejection>,
+) -> impl IntoResponse {
     let request_id = next_request_id();
-    tracing::info!(
-        request_id,
-        difficulty = %params.difficulty,
-        "Received board generation request",
-    );
 
-    let config = match GameConfig::for_server_from_difficulty(&params.difficulty) {
-        Ok(cfg) => cfg,
-        Err(msg) => {
-            tracing::warn!(request_id, error = %msg, "Bad difficulty parameter");
+    // Report malformed parameters (e.g. columns=abc) as JSON, like every other error.
+    let params = match query {
+        Ok(Query(params)) => params,
+        Err(rejection) => {
+            let msg = rejection.body_text();
+            tracing::warn!(request_id, error = %msg, "Bad board parameters");
             return (
                 StatusCode::BAD_REQUEST,
                 Json(json!({ "request_id": request_id, "error": msg })),
@@ -34,22 +282,40 @@ pub async fn board_handler(Query(params): Query<BoardRequest>) -> impl IntoRespo
         }
     };
 
-    let board = match board::Board::generate_solvable_board(&config, Some(request_id)) {
-        Ok(b) => b,
+    tracing::info!(
+        request_id,
+        difficulty = ?params.difficulty,
+        columns = ?params.columns,
+        rows = ?params.rows,
+        moves_required = ?params.moves_required,
+        max_moves = ?params.max_moves,
+        exact_moves = ?params.exact_moves,
+        rock_percentage = ?params.rock_percentage,
+        seed = ?params.seed,
+        "Received board generation request",
+    );
+
+    let config = match params.to_config() {
+        Ok(cfg) => cfg,
         Err(e) => {
-            tracing::error!(
-                request_id,
-                error = %e,
-                "Failed to generate solvable board"
-            );
-            return (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(json!({
-                    "request_id": request_id,
-                    "error": "failed to generate solvable board"
-                })),
-            );
+            tracing::warn!(request_id, error = %e, "Bad board parameters");
+            return error_response(request_id, &e);
+        }
+    };
+
+    let pooled = params
+        .pooled_difficulty()
+        .and_then(|difficulty| state.pool.take(difficulty));
+
+    let board = match pooled {
+        Some(b) => {
+            tracing::info!(request_id, "Serving board from pool");
+            b
         }
+        None => match generate_board(&state, config, request_id, &params.metrics_label()).await {
+            Ok(b) => b,
+            Err(response) => return response,
+        },
     };
 
     let json_str = board.get_layout_json();
@@ -79,14 +345,162 @@ pub async fn board_handler(Query(params): Query<BoardRequest>) -> impl IntoRespo
     }
 }
 
+pub async fn solve_handler(
+    State(state): State<AppState>,
+    Json(params): Json<SolveRequest>,
+) -> impl IntoResponse {
+    let request_id = next_request_id();
+    tracing::info!(request_id, "Received solve request");
+
+    let board = match board::Board::from_layout(params.board) {
+        Ok(b) => b,
+        Err(e) => {
+            tracing::warn!(request_id, error = %e, "Invalid board");
+            return error_response(request_id, &e);
+        }
+    };
+
+    let deadline = Instant::now() + state.generation_timeout;
+    let solution = match run_blocking(&state, deadline, move || board.solve()).await {
+        Ok(solution) => solution,
+        Err(failure) => return blocking_failure_response(request_id, failure, "solving board"),
+    };
+
+    match solution.get_solution_string() {
+        Some(steps) => {
+            tracing::info!(request_id, "Successfully solved board");
+            (
+                StatusCode::OK,
+                Json(json!({
+                    "request_id": request_id,
+                    "solution": steps,
+                    "length": steps.len(),
+                    "edges_traversed": solution.edges_traversed,
+                })),
+            )
+        }
+        None => {
+            tracing::info!(request_id, "Board is unsolvable");
+            (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "request_id": request_id,
+                    "error": "board is unsolvable",
+                    "edges_traversed": solution.edges_traversed,
+                })),
+            )
+        }
+    }
+}
+
+pub async fn validate_handler(
+    State(state): State<AppState>,
+    Json(params): Json<ValidateRequest>,
+) -> impl IntoResponse {
+    let request_id = next_request_id();
+    // The moves are client input of any length, so only their length is logged.
+    tracing::info!(
+        request_id,
+        moves_len = params.moves.len(),
+        "Received validate request"
+    );
+
+    let moves: Result<Vec<Direction>, GlacierError> = params
+        .moves
+        .chars()
+        .map(|c| {
+            // Like a bad board, a bad move is a problem with the request body.
+            Direction::from_char(c).ok_or_else(|| {
+                GlacierError::InvalidBoard(format!(
+                    "Invalid move '{}'. Expected one of: U, D, L, R.",
+                    c
+                ))
+            })
+        })
+        .collect();
+
+    let (board, moves) = match (board::Board::from_layout(params.board), moves) {
+        (Ok(b), Ok(m)) => (b, m),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::warn!(request_id, error = %e, "Bad validate request");
+            return error_response(request_id, &e);
+        }
+    };
+
+    let deadline = Instant::now() + state.generation_timeout;
+    let work = move || (board.replay(&moves), board.solve());
+    let (replay, solution) = match run_blocking(&state, deadline, work).await {
+        Ok(result) => result,
+        Err(failure) => return blocking_failure_response(request_id, failure, "validating moves"),
+    };
+    let optimal_moves = solution.steps.map(|steps| steps.len());
+    let extra_moves = optimal_moves
+        .filter(|_| replay.reached_end)
+        .map(|optimal| replay.moves.saturating_sub(optimal));
+
+    tracing::info!(
+        request_id,
+        reached_end = replay.reached_end,
+        moves = replay.moves,
+        "Validated moves"
+    );
+    (
+        StatusCode::OK,
+        Json(json!({
+            "request_id": request_id,
+            "final_position": replay.final_position,
+            "reached_end": replay.reached_end,
+            "moves": replay.moves,
+            "optimal_moves": optimal_moves,
+            "extra_moves": extra_moves,
+        })),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::web_server::state::ServerConfig;
     use axum::extract::Query;
     use axum::http::StatusCode;
     use axum::response::{IntoResponse, Response};
     use http_body_util::BodyExt;
-    use serde_json::Value;
+    use std::time::Duration;
+
+    fn test_state() -> AppState {
+        AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_secs(30),
+            max_concurrent_generations: 2,
+            pool_size: 0,
+        })
+    }
+
+    /*
+    WWWWW
+    S   W
+    W R W
+    W   W
+    WWWEW
+    */
+    fn simple_layout() -> Value {
+        json!({
+            "rows": 5,
+            "cols": 5,
+            "start": [0, 1],
+            "end": [3, 4],
+            "rocks": [[2, 2]],
+            "grid": ["WWWWW", "S   W", "W R W", "W   W", "WWWEW"]
+        })
+    }
+
+    fn solve_request(layout: Value) -> Json<SolveRequest> {
+        Json(serde_json::from_value(json!({ "board": layout })).unwrap())
+    }
+
+    fn validate_request(moves: &str) -> Json<ValidateRequest> {
+        Json(serde_json::from_value(json!({ "board": simple_layout(), "moves": moves })).unwrap())
+    }
 
     // Helper to turn any IntoResponse into (StatusCode, JSON body)
     async fn status_and_json(res: impl IntoResponse) -> (StatusCode, Value) {
@@ -100,19 +514,22 @@ mod tests {
 
     #[tokio::test]
     async fn health_handler_returns_ok_status_and_json() {
-        let (status, body) = status_and_json(health_handler().await).await;
+        let (status, body) = status_and_json(health_handler(State(test_state())).await).await;
 
         assert_eq!(status, StatusCode::OK);
         assert_eq!(body["status"], "ok");
+        assert_eq!(body["pool"]["extreme"], 0);
     }
 
     #[tokio::test]
     async fn board_handler_rejects_unknown_difficulty() {
         let req = BoardRequest {
-            difficulty: "insane".to_string(),
+            difficulty: Some("insane".to_string()),
+            ..BoardRequest::default()
         };
 
-        let (status, body) = status_and_json(board_handler(Query(req)).await).await;
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
 
         assert_eq!(status, StatusCode::BAD_REQUEST);
 
@@ -128,10 +545,12 @@ mod tests {
     #[tokio::test]
     async fn board_handler_accepts_valid_difficulty_and_returns_board() {
         let req = BoardRequest {
-            difficulty: "easy".to_string(),
+            difficulty: Some("easy".to_string()),
+            ..BoardRequest::default()
         };
 
-        let (status, body) = status_and_json(board_handler(Query(req)).await).await;
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
 
         assert_eq!(status, StatusCode::OK);
 
@@ -147,4 +566,380 @@ mod tests {
             "expected board field in response: {body:?}"
         );
     }
+
+    #[tokio::test]
+    async fn board_handler_with_seed_is_reproducible() {
+        let request = || BoardRequest {
+            difficulty: Some("easy".to_string()),
+            seed: Some(42),
+            ..BoardRequest::default()
+        };
+
+        let (_, first) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(request()))).await).await;
+        let (_, second) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(request()))).await).await;
+
+        assert_eq!(first["board"], second["board"]);
+        assert!(first["board"]["seed"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn board_handler_seed_round_trips_through_json() {
+        // Above 2^53, where a JavaScript number could no longer hold the seed exactly.
+        let request = |seed| BoardRequest {
+            difficulty: Some("easy".to_string()),
+            seed: Some(seed),
+            ..BoardRequest::default()
+        };
+
+        let (_, first) = status_and_json(
+            board_handler(State(test_state()), Ok(Query(request(u64::MAX - 5)))).await,
+        )
+        .await;
+        let seed: u64 = first["board"]["seed"].as_str().unwrap().parse().unwrap();
+
+        // The returned seed regenerates the same board on its own.
+        let (_, again) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(request(seed)))).await)
+                .await;
+        assert_eq!(again["board"], first["board"]);
+
+        let layout: BoardLayout = serde_json::from_value(first["board"].clone()).unwrap();
+        assert!(layout.validate().is_ok());
+    }
+
+    #[tokio::test]
+    async fn board_handler_applies_custom_level_parameters() {
+        let req = BoardRequest {
+            difficulty: Some("easy".to_string()),
+            columns: Some(4),
+            rows: Some(3),
+            moves_required: Some(2),
+            rock_percentage: Some(20),
+            ..BoardRequest::default()
+        };
+
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
+
+        assert_eq!(status, StatusCode::OK);
+        // Custom dimensions count playable tiles, so the border adds 2.
+        assert_eq!(body["board"]["cols"], 6);
+        assert_eq!(body["board"]["rows"], 5);
+    }
+
+    #[tokio::test]
+    async fn board_handler_honours_exact_moves() {
+        let req = BoardRequest {
+            difficulty: Some("easy".to_string()),
+            exact_moves: Some(5),
+            seed: Some(7),
+            ..BoardRequest::default()
+        };
+
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["board"]["difficulty"]["optimal_moves"], 5);
+    }
+
+    #[tokio::test]
+    async fn board_handler_rejects_max_moves_below_moves_required() {
+        let req = BoardRequest {
+            moves_required: Some(10),
+            max_moves: Some(8),
+            ..BoardRequest::default()
+        };
+
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["error"],
+            "max_moves must be at least the 10 moves required, but was 8."
+        );
+    }
+
+    #[tokio::test]
+    async fn board_handler_rejects_out_of_range_custom_parameters() {
+        let req = BoardRequest {
+            columns: Some(21),
+            ..BoardRequest::default()
+        };
+
+        let (status, body) =
+            status_and_json(board_handler(State(test_state()), Ok(Query(req))).await).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(
+            body["error"],
+            "columns must be between 3 and 20, but was 21."
+        );
+    }
+
+    #[tokio::test]
+    async fn board_handler_rejects_malformed_query_as_json() {
+        let uri: axum::http::Uri = "/board?columns=abc".parse().unwrap();
+        let query = Query::<BoardRequest>::try_from_uri(&uri);
+
+        let (status, body) = status_and_json(board_handler(State(test_state()), query).await).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().is_some());
+    }
+
+    #[tokio::test]
+    async fn solve_handler_returns_shortest_solution() {
+        let (status, body) = status_and_json(
+            solve_handler(State(test_state()), solve_request(simple_layout())).await,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["solution"], "RD");
+        assert_eq!(body["length"], 2);
+        assert!(body["edges_traversed"].as_u64().is_some());
+    }
+
+    #[tokio::test]
+    async fn solve_handler_returns_unprocessable_for_unsolvable_board() {
+        let mut layout = simple_layout();
+        layout["rocks"] = json!([[2, 1], [1, 2]]);
+        layout["grid"] = json!(["WWWWW", "S R W", "WR  W", "W   W", "WWWEW"]);
+
+        let (status, body) =
+            status_and_json(solve_handler(State(test_state()), solve_request(layout)).await).await;
+
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(body["error"], "board is unsolvable");
+    }
+
+    #[tokio::test]
+    async fn solve_handler_rejects_inconsistent_board() {
+        let mut layout = simple_layout();
+        layout["rocks"] = json!([]);
+
+        let (status, body) =
+            status_and_json(solve_handler(State(test_state()), solve_request(layout)).await).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().is_some());
+    }
+
+    // Every slot is taken, so work that needs one waits until the deadline.
+    fn busy_state() -> AppState {
+        AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_millis(50),
+            max_concurrent_generations: 0,
+            pool_size: 0,
+        })
+    }
+
+    #[tokio::test]
+    async fn solve_handler_waits_for_a_generation_slot() {
+        let (status, body) = status_and_json(
+            solve_handler(State(busy_state()), solve_request(simple_layout())).await,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.get("request_id").and_then(Value::as_u64).is_some());
+    }
+
+    #[tokio::test]
+    async fn solve_handler_rejects_oversized_board() {
+        let top = format!("WS{}", "W".repeat(298));
+        let middle = format!("W{}W", " ".repeat(298));
+        let bottom = format!("{}EW", "W".repeat(298));
+        let layout = json!({
+            "rows": 3,
+            "cols": 300,
+            "start": [1, 0],
+            "end": [298, 2],
+            "rocks": [],
+            "grid": [top, middle, bottom]
+        });
+
+        let (status, body) =
+            status_and_json(solve_handler(State(test_state()), solve_request(layout)).await).await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].as_str().unwrap().contains("at most"));
+    }
+
+    #[tokio::test]
+    async fn validate_handler_accepts_optimal_moves() {
+        let (status, body) =
+            status_and_json(validate_handler(State(test_state()), validate_request("RD")).await)
+                .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reached_end"], true);
+        assert_eq!(body["final_position"], json!([3, 4]));
+        assert_eq!(body["moves"], 2);
+        assert_eq!(body["optimal_moves"], 2);
+        assert_eq!(body["extra_moves"], 0);
+    }
+
+    #[tokio::test]
+    async fn validate_handler_reports_unfinished_moves() {
+        let (status, body) =
+            status_and_json(validate_handler(State(test_state()), validate_request("R")).await)
+                .await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["reached_end"], false);
+        assert_eq!(body["final_position"], json!([3, 1]));
+        assert_eq!(body["moves"], 1);
+        assert_eq!(body["extra_moves"], Value::Null);
+    }
+
+    #[tokio::test]
+    async fn validate_handler_rejects_unknown_moves() {
+        let (status, body) =
+            status_and_json(validate_handler(State(test_state()), validate_request("RX")).await)
+                .await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let err = body["error"].as_str().unwrap_or("");
+        assert!(err.contains("'X'"), "unexpected error message: {err}");
+    }
+
+    #[tokio::test]
+    async fn validate_handler_waits_for_a_generation_slot() {
+        let (status, _) =
+            status_and_json(validate_handler(State(busy_state()), validate_request("RD")).await)
+                .await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    // A 3x3 board can never need 35 moves, so generation runs until it is cancelled.
+    fn impossible_request() -> BoardRequest {
+        BoardRequest {
+            columns: Some(3),
+            rows: Some(3),
+            moves_required: Some(35),
+            ..BoardRequest::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn board_handler_times_out_slow_generation() {
+        let state = AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_millis(50),
+            max_concurrent_generations: 1,
+            pool_size: 0,
+        });
+
+        let (status, body) = status_and_json(
+            board_handler(State(state.clone()), Ok(Query(impossible_request()))).await,
+        )
+        .await;
+
+        assert_eq!(status, StatusCode::GATEWAY_TIMEOUT);
+        assert!(body.get("request_id").and_then(Value::as_u64).is_some());
+
+        // The cancelled generation releases its slot shortly after the timeout.
+        let permit =
+            tokio::time::timeout(Duration::from_secs(5), state.generation_slots.acquire()).await;
+        assert!(permit.is_ok(), "generation slot was never released");
+    }
+
+    #[tokio::test]
+    async fn board_handler_returns_unavailable_when_all_slots_are_busy() {
+        let state = AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_millis(50),
+            max_concurrent_generations: 1,
+            pool_size: 0,
+        });
+        let _busy = state
+            .generation_slots
+            .clone()
+            .acquire_owned()
+            .await
+            .unwrap();
+
+        let req = BoardRequest {
+            difficulty: Some("easy".to_string()),
+            ..BoardRequest::default()
+        };
+        let (status, body) =
+            status_and_json(board_handler(State(state), Ok(Query(req))).await).await;
+
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(body.get("request_id").and_then(Value::as_u64).is_some());
+    }
+
+    #[tokio::test]
+    async fn board_handler_serves_plain_difficulty_from_pool() {
+        let state = AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_secs(30),
+            max_concurrent_generations: 1,
+            pool_size: 1,
+        });
+        let config = GameConfig::for_server_from_difficulty("easy").unwrap();
+        state
+            .pool
+            .put("easy", board::Board::generate_random_board(&config, 77));
+
+        let req = || BoardRequest {
+            difficulty: Some("easy".to_string()),
+            ..BoardRequest::default()
+        };
+        let (status, body) =
+            status_and_json(board_handler(State(state.clone()), Ok(Query(req()))).await).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["board"]["seed"], "77");
+        assert_eq!(state.pool.depths()["easy"], 0);
+
+        // Requests with custom parameters always generate a fresh board.
+        state
+            .pool
+            .put("easy", board::Board::generate_random_board(&config, 78));
+        let custom = BoardRequest {
+            rows: Some(5),
+            ..req()
+        };
+        let (status, body) =
+            status_and_json(board_handler(State(state.clone()), Ok(Query(custom))).await).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_ne!(body["board"]["seed"], "78");
+        assert_eq!(state.pool.depths()["easy"], 1);
+    }
+
+    #[tokio::test]
+    async fn board_handler_is_not_held_up_by_pool_refills() {
+        let state = AppState::new(&ServerConfig {
+            bind: "127.0.0.1:0".to_string(),
+            generation_timeout: Duration::from_secs(5),
+            // One slot for requests and one for refills.
+            max_concurrent_generations: 2,
+            pool_size: 3,
+        });
+        // Every difficulty's pool starts empty, so the workers are all busy refilling.
+        state
+            .pool
+            .spawn_workers(Arc::clone(&state.pool_slots), Duration::from_secs(30));
+
+        let req = BoardRequest {
+            difficulty: Some("easy".to_string()),
+            seed: Some(5),
+            ..BoardRequest::default()
+        };
+        let (status, _) =
+            status_and_json(board_handler(State(state.clone()), Ok(Query(req))).await).await;
+        state.pool.stop();
+
+        assert_eq!(status, StatusCode::OK);
+    }
 }
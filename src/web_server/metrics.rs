@@ -1,7 +1,282 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        LazyLock, Mutex,
+    },
+    time::Duration,
+};
+
+use axum::{
+    extract::{MatchedPath, Request},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::board::Board;
 
 static REQUEST_COUNTER: AtomicU64 = AtomicU64::new(1);
 
 pub(crate) fn next_request_id() -> u64 {
     REQUEST_COUNTER.fetch_add(1, Ordering::SeqCst)
 }
+
+const LATENCY_BUCKETS: &[f64] = &[
+    0.001, 0.005, 0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0,
+];
+const ATTEMPT_BUCKETS: &[f64] = &[1.0, 10.0, 100.0, 1e3, 1e4, 1e5, 1e6];
+const EDGE_BUCKETS: &[f64] = &[1.0, 5.0, 10.0, 25.0, 50.0, 100.0, 250.0, 500.0, 1000.0];
+
+struct Histogram {
+    buckets: &'static [f64],
+    counts: Vec<u64>, // Per bucket, not cumulative
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Histogram {
+            buckets,
+            counts: vec![0; buckets.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(i) = self.buckets.iter().position(|&le| value <= le) {
+            self.counts[i] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+// Escape a label value as required by the Prometheus text format.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[derive(Default)]
+struct HistogramFamily(BTreeMap<String, Histogram>);
+
+impl HistogramFamily {
+    fn observe(&mut self, difficulty: &str, buckets: &'static [f64], value: f64) {
+        self.0
+            .entry(difficulty.to_string())
+            .or_insert_with(|| Histogram::new(buckets))
+            .observe(value);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}");
+        let _ = writeln!(out, "# TYPE {name} histogram");
+
+        for (difficulty, histogram) in &self.0 {
+            let difficulty = escape(difficulty);
+            let mut cumulative = 0;
+            for (le, count) in histogram.buckets.iter().zip(&histogram.counts) {
+                cumulative += count;
+                let _ = writeln!(
+                    out,
+                    "{name}_bucket{{difficulty=\"{difficulty}\",le=\"{le}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                out,
+                "{name}_bucket{{difficulty=\"{difficulty}\",le=\"+Inf\"}} {}",
+                histogram.count
+            );
+            let _ = writeln!(
+                out,
+                "{name}_sum{{difficulty=\"{difficulty}\"}} {}",
+                histogram.sum
+            );
+            let _ = writeln!(
+                out,
+                "{name}_count{{difficulty=\"{difficulty}\"}} {}",
+                histogram.count
+            );
+        }
+    }
+}
+
+#[derive(Default)]
+struct Inner {
+    requests: BTreeMap<(String, u16), u64>, // (route, status)
+    generation_seconds: HistogramFamily,
+    generation_attempts: HistogramFamily,
+    edges_traversed: HistogramFamily,
+    generation_failures: BTreeMap<(String, &'static str), u64>, // (difficulty, reason)
+}
+
+#[derive(Default)]
+pub(crate) struct Metrics {
+    inner: Mutex<Inner>,
+}
+
+impl Metrics {
+    pub(crate) fn record_request(&self, route: &str, status: StatusCode) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner
+            .requests
+            .entry((route.to_string(), status.as_u16()))
+            .or_default() += 1;
+    }
+
+    pub(crate) fn record_generation(&self, difficulty: &str, elapsed: Duration, board: &Board) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .generation_seconds
+            .observe(difficulty, LATENCY_BUCKETS, elapsed.as_secs_f64());
+        inner.generation_attempts.observe(
+            difficulty,
+            ATTEMPT_BUCKETS,
+            f64::from(board.generation_attempts),
+        );
+        if let Some(solution) = &board.solution {
+            inner.edges_traversed.observe(
+                difficulty,
+                EDGE_BUCKETS,
+                f64::from(solution.edges_traversed),
+            );
+        }
+    }
+
+    pub(crate) fn record_generation_failure(&self, difficulty: &str, reason: &'static str) {
+        let mut inner = self.inner.lock().unwrap();
+        *inner
+            .generation_failures
+            .entry((difficulty.to_string(), reason))
+            .or_default() += 1;
+    }
+
+    pub(crate) fn render(&self) -> String {
+        let inner = self.inner.lock().unwrap();
+        let mut out = String::new();
+
+        out.push_str("# HELP glacier_http_requests_total HTTP requests by route and status.\n");
+        out.push_str("# TYPE glacier_http_requests_total counter\n");
+        for ((route, status), count) in &inner.requests {
+            let _ = writeln!(
+                out,
+                "glacier_http_requests_total{{route=\"{}\",status=\"{status}\"}} {count}",
+                escape(route)
+            );
+        }
+
+        inner.generation_seconds.render(
+            &mut out,
+            "glacier_board_generation_seconds",
+            "Time spent generating a solvable board.",
+        );
+        inner.generation_attempts.render(
+            &mut out,
+            "glacier_board_generation_attempts",
+            "Random boards tried before a solvable one was found.",
+        );
+        inner.edges_traversed.render(
+            &mut out,
+            "glacier_solver_edges_traversed",
+            "Edges traversed by the solver for the accepted board.",
+        );
+
+        out.push_str("# HELP glacier_board_generation_failures_total Board generations that did not produce a board.\n");
+        out.push_str("# TYPE glacier_board_generation_failures_total counter\n");
+        for ((difficulty, reason), count) in &inner.generation_failures {
+            let _ = writeln!(
+                out,
+                "glacier_board_generation_failures_total{{difficulty=\"{}\",reason=\"{reason}\"}} {count}",
+                escape(difficulty)
+            );
+        }
+
+        out
+    }
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::default);
+
+pub(crate) fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+// Middleware counting every response by its route template (e.g. "/board") and status code.
+pub(crate) async fn track_requests(request: Request, next: Next) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or("unmatched", MatchedPath::as_str)
+        .to_string();
+
+    let response = next.run(request).await;
+    metrics().record_request(&route, response.status());
+    response
+}
+
+pub async fn metrics_handler() -> impl IntoResponse {
+    (
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics().render(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_state::GameConfig;
+
+    #[test]
+    fn histogram_buckets_are_cumulative() {
+        let mut family = HistogramFamily::default();
+        family.observe("easy", &[1.0, 10.0], 0.5);
+        family.observe("easy", &[1.0, 10.0], 5.0);
+        family.observe("easy", &[1.0, 10.0], 50.0);
+
+        let mut out = String::new();
+        family.render(&mut out, "test_metric", "Test.");
+
+        assert!(out.contains("test_metric_bucket{difficulty=\"easy\",le=\"1\"} 1\n"));
+        assert!(out.contains("test_metric_bucket{difficulty=\"easy\",le=\"10\"} 2\n"));
+        assert!(out.contains("test_metric_bucket{difficulty=\"easy\",le=\"+Inf\"} 3\n"));
+        assert!(out.contains("test_metric_sum{difficulty=\"easy\"} 55.5\n"));
+        assert!(out.contains("test_metric_count{difficulty=\"easy\"} 3\n"));
+    }
+
+    #[test]
+    fn render_includes_requests_generations_and_failures() {
+        let metrics = Metrics::default();
+        let config = GameConfig {
+            board_only: true,
+            ..GameConfig::default()
+        };
+        let board = Board::generate_solvable_board(&config, None).unwrap();
+
+        metrics.record_request("/board", StatusCode::OK);
+        metrics.record_request("/board", StatusCode::OK);
+        metrics.record_generation("easy", Duration::from_millis(3), &board);
+        metrics.record_generation_failure("extreme", "timeout");
+
+        let out = metrics.render();
+
+        assert!(out.contains("glacier_http_requests_total{route=\"/board\",status=\"200\"} 2\n"));
+        assert!(out.contains("glacier_board_generation_seconds_count{difficulty=\"easy\"} 1\n"));
+        assert!(out.contains("glacier_board_generation_attempts_count{difficulty=\"easy\"} 1\n"));
+        assert!(out.contains("glacier_solver_edges_traversed_count{difficulty=\"easy\"} 1\n"));
+        assert!(out.contains(
+            "glacier_board_generation_failures_total{difficulty=\"extreme\",reason=\"timeout\"} 1\n"
+        ));
+    }
+
+    #[test]
+    fn escape_quotes_label_values() {
+        assert_eq!(escape("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+}
@@ -1,26 +1,61 @@
 mod handlers;
 mod metrics;
+mod pool;
+mod state;
 
-use axum::{http::Method, routing::get, Router};
-use std::{error::Error, net::SocketAddr};
+pub use state::ServerConfig;
+
+use axum::{
+    http::Method,
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use state::AppState;
+use std::{net::SocketAddr, sync::Arc};
 use tower_http::cors::{Any, CorsLayer};
 
-pub async fn start_web_server(bind: String) -> Result<(), Box<dyn Error>> {
-    let addr: SocketAddr = bind.parse()?;
+use crate::error::GlacierError;
+
+pub async fn start_web_server(config: ServerConfig) -> Result<(), GlacierError> {
+    let addr: SocketAddr = config.bind.parse().map_err(|e| {
+        GlacierError::InvalidConfig(format!("Invalid bind address '{}': {}", config.bind, e))
+    })?;
 
     let cors = CorsLayer::new()
         .allow_origin(Any) // Do not restrict the origin
-        .allow_methods([Method::GET])
+        .allow_methods([Method::GET, Method::POST])
         .allow_headers(Any);
 
+    let state = AppState::new(&config);
+    let pool = Arc::clone(&state.pool);
+    pool.spawn_workers(Arc::clone(&state.pool_slots), config.generation_timeout);
+
     let app = Router::new()
         .route("/health", get(handlers::health_handler))
         .route("/board", get(handlers::board_handler))
-        .layer(cors);
+        .route("/solve", post(handlers::solve_handler))
+        .route("/validate", post(handlers::validate_handler))
+        .route("/metrics", get(metrics::metrics_handler))
+        .layer(middleware::from_fn(metrics::track_requests))
+        .layer(cors)
+        .with_state(state);
 
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    println!("listening on http://{}", listener.local_addr()?);
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| GlacierError::io(format!("Could not bind to {}", addr), e))?;
+    // The bound address, so a port of 0 shows the port the OS picked.
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| GlacierError::io("Could not read the bound address", e))?;
+    println!("listening on http://{}", local_addr);
 
-    axum::serve(listener, app).await?;
-    Ok(())
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async move {
+            // Ctrl-C stops the server. Pool workers cancel whatever they are generating.
+            let _ = tokio::signal::ctrl_c().await;
+            pool.stop();
+        })
+        .await
+        .map_err(|e| GlacierError::io("Server error", e))
 }
@@ -0,0 +1,251 @@
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::{
+    sync::{Notify, Semaphore},
+    task::{self, JoinHandle},
+};
+
+use super::metrics::metrics;
+use crate::{board::Board, error::GlacierError, game_state::GameConfig};
+
+pub const DIFFICULTIES: [&str; 4] = ["easy", "medium", "hard", "extreme"];
+
+struct DifficultyPool {
+    boards: Mutex<VecDeque<Board>>,
+    // Woken whenever a board is taken, so the worker can top the pool back up.
+    refill: Notify,
+}
+
+// Ready-made boards for each difficulty, so plain /board?difficulty=... requests are answered
+// instantly while background workers refill the pool.
+pub struct BoardPool {
+    capacity: usize,
+    pools: HashMap<&'static str, DifficultyPool>,
+    // Set on shutdown. Workers stop, and cancel any generation in progress.
+    stopped: AtomicBool,
+}
+
+impl BoardPool {
+    pub fn new(capacity: usize) -> Self {
+        let pools = DIFFICULTIES
+            .iter()
+            .map(|&difficulty| {
+                let pool = DifficultyPool {
+                    boards: Mutex::new(VecDeque::with_capacity(capacity)),
+                    refill: Notify::new(),
+                };
+                (difficulty, pool)
+            })
+            .collect();
+
+        BoardPool {
+            capacity,
+            pools,
+            stopped: AtomicBool::new(false),
+        }
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::Relaxed);
+        for pool in self.pools.values() {
+            pool.refill.notify_one();
+        }
+    }
+
+    fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
+    }
+
+    fn pool(&self, difficulty: &str) -> Option<&DifficultyPool> {
+        self.pools
+            .get(difficulty.trim().to_ascii_lowercase().as_str())
+    }
+
+    pub fn take(&self, difficulty: &str) -> Option<Board> {
+        let pool = self.pool(difficulty)?;
+        let board = pool.boards.lock().unwrap().pop_front();
+        if board.is_some() {
+            pool.refill.notify_one();
+        }
+        board
+    }
+
+    // Returns false (dropping the board) if the pool is already full.
+    pub fn put(&self, difficulty: &str, board: Board) -> bool {
+        match self.pool(difficulty) {
+            Some(pool) => {
+                let mut boards = pool.boards.lock().unwrap();
+                if boards.len() < self.capacity {
+                    boards.push_back(board);
+                    true
+                } else {
+                    false
+                }
+            }
+            None => false,
+        }
+    }
+
+    pub fn depths(&self) -> BTreeMap<&'static str, usize> {
+        self.pools
+            .iter()
+            .map(|(&difficulty, pool)| (difficulty, pool.boards.lock().unwrap().len()))
+            .collect()
+    }
+
+    fn depth(&self, difficulty: &str) -> usize {
+        self.pool(difficulty)
+            .map_or(0, |pool| pool.boards.lock().unwrap().len())
+    }
+
+    // Start one refill worker per difficulty. Workers take turns on `pool_slots`, which are split
+    // off the slots requests generate in, so refilling never makes a request wait. Each refill
+    // gives up after `time_budget`, like a request would.
+    pub fn spawn_workers(self: &Arc<Self>, pool_slots: Arc<Semaphore>, time_budget: Duration) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        for difficulty in DIFFICULTIES {
+            self.spawn_worker(difficulty, Arc::clone(&pool_slots), time_budget);
+        }
+    }
+
+    fn spawn_worker(
+        self: &Arc<Self>,
+        difficulty: &'static str,
+        pool_slots: Arc<Semaphore>,
+        time_budget: Duration,
+    ) -> JoinHandle<()> {
+        let pool = Arc::clone(self);
+        tokio::spawn(async move {
+            pool.refill_worker(difficulty, pool_slots, time_budget)
+                .await
+        })
+    }
+
+    async fn refill_worker(
+        self: Arc<Self>,
+        difficulty: &'static str,
+        slots: Arc<Semaphore>,
+        time_budget: Duration,
+    ) {
+        let config = GameConfig {
+            time_budget: Some(time_budget),
+            ..GameConfig::for_server_from_difficulty(difficulty)
+                .expect("pool difficulties are valid")
+        };
+
+        while !self.is_stopped() {
+            while self.depth(difficulty) < self.capacity && !self.is_stopped() {
+                let Ok(permit) = Arc::clone(&slots).acquire_owned().await else {
+                    return;
+                };
+                let config = config.clone();
+                let pool = Arc::clone(&self);
+                let result = task::spawn_blocking(move || {
+                    let _permit = permit;
+                    let started = Instant::now();
+                    let result =
+                        Board::generate_solvable_board_cancellable(&config, None, &pool.stopped);
+                    (result, started.elapsed())
+                })
+                .await;
+
+                match result {
+                    Ok((Ok(board), elapsed)) => {
+                        metrics().record_generation(difficulty, elapsed, &board);
+                        self.put(difficulty, board);
+                    }
+                    Ok((Err(GlacierError::GenerationCancelled), _)) => return,
+                    Ok((Err(e), _)) => {
+                        tracing::error!(difficulty, error = %e, "Failed to refill board pool");
+                        metrics().record_generation_failure(difficulty, "exhausted");
+                        // Back off rather than spinning on a config that keeps failing.
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                    Err(e) => {
+                        tracing::error!(difficulty, error = %e, "Board pool worker task failed");
+                        tokio::time::sleep(Duration::from_secs(1)).await;
+                    }
+                }
+            }
+
+            if let Some(pool) = self.pool(difficulty) {
+                pool.refill.notified().await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn easy_board(seed: u64) -> Board {
+        let config = GameConfig::for_server_from_difficulty("easy").unwrap();
+        Board::generate_random_board(&config, seed)
+    }
+
+    #[test]
+    fn take_returns_boards_in_order() {
+        let pool = BoardPool::new(2);
+        assert!(pool.put("easy", easy_board(1)));
+        assert!(pool.put("EASY", easy_board(2)));
+
+        assert_eq!(pool.take("easy").and_then(|b| b.seed()), Some(1));
+        assert_eq!(pool.take("easy").and_then(|b| b.seed()), Some(2));
+        assert!(pool.take("easy").is_none());
+    }
+
+    #[test]
+    fn put_respects_capacity() {
+        let pool = BoardPool::new(1);
+        assert!(pool.put("hard", easy_board(1)));
+        assert!(!pool.put("hard", easy_board(2)));
+        assert!(!pool.put("insane", easy_board(3)));
+
+        let depths = pool.depths();
+        assert_eq!(depths["hard"], 1);
+        assert_eq!(depths["easy"], 0);
+        assert_eq!(depths.len(), DIFFICULTIES.len());
+    }
+
+    #[tokio::test]
+    async fn workers_fill_the_pool() {
+        let pool = Arc::new(BoardPool::new(1));
+        pool.spawn_worker("easy", Arc::new(Semaphore::new(1)), Duration::from_secs(10));
+
+        // Easy boards are quick to generate, so the worker fills its pool almost immediately.
+        let filled = tokio::time::timeout(Duration::from_secs(10), async {
+            while pool.depth("easy") < 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+
+        assert!(filled.is_ok(), "easy pool was never filled");
+    }
+
+    #[tokio::test]
+    async fn stop_ends_workers_and_cancels_generation() {
+        let pool = Arc::new(BoardPool::new(3));
+        let slots = Arc::new(Semaphore::new(1));
+        let worker = pool.spawn_worker("extreme", Arc::clone(&slots), Duration::from_secs(60));
+
+        // Let the worker start generating, then shut down.
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        pool.stop();
+
+        let stopped = tokio::time::timeout(Duration::from_secs(10), worker).await;
+        assert!(stopped.is_ok(), "worker kept running after stop");
+        assert_eq!(slots.available_permits(), 1);
+    }
+}
@@ -0,0 +1,86 @@
+use std::{sync::Arc, time::Duration};
+
+use tokio::sync::Semaphore;
+
+use super::pool::BoardPool;
+
+pub struct ServerConfig {
+    pub bind: String,
+    // Wall-clock limit for a single /board, /solve or /validate request, including time spent
+    // waiting for a free slot.
+    pub generation_timeout: Duration,
+    pub max_concurrent_generations: usize,
+    // Number of ready boards kept per difficulty (0 disables the pool).
+    pub pool_size: usize,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    // Generating and solving boards is CPU-bound, so each running job holds one of these permits.
+    pub generation_slots: Arc<Semaphore>,
+    // Pool refills get their own half of max_concurrent_generations, so a refilling pool never
+    // makes requests wait. With a single slot there is nothing to split, and this is the same
+    // semaphore as generation_slots.
+    pub pool_slots: Arc<Semaphore>,
+    pub generation_timeout: Duration,
+    pub pool: Arc<BoardPool>,
+}
+
+impl AppState {
+    pub fn new(config: &ServerConfig) -> Self {
+        // Without a pool, requests get every slot.
+        let pool_share = if config.pool_size == 0 {
+            0
+        } else {
+            config.max_concurrent_generations / 2
+        };
+        let generation_slots = Arc::new(Semaphore::new(
+            config.max_concurrent_generations - pool_share,
+        ));
+        let pool_slots = if pool_share == 0 {
+            Arc::clone(&generation_slots)
+        } else {
+            Arc::new(Semaphore::new(pool_share))
+        };
+
+        AppState {
+            generation_slots,
+            pool_slots,
+            generation_timeout: config.generation_timeout,
+            pool: Arc::new(BoardPool::new(config.pool_size)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Takes every permit left in `slots`.
+    fn hold_all(slots: &Arc<Semaphore>) -> Vec<tokio::sync::OwnedSemaphorePermit> {
+        std::iter::from_fn(|| Arc::clone(slots).try_acquire_owned().ok()).collect()
+    }
+
+    #[test]
+    fn requests_and_refills_never_exceed_max_concurrent_generations() {
+        for max_concurrent_generations in 1..=6 {
+            for pool_size in [0, 3] {
+                let state = AppState::new(&ServerConfig {
+                    bind: "127.0.0.1:0".to_string(),
+                    generation_timeout: Duration::from_secs(30),
+                    max_concurrent_generations,
+                    pool_size,
+                });
+
+                let requests = hold_all(&state.generation_slots);
+                let refills = hold_all(&state.pool_slots);
+                assert_eq!(requests.len() + refills.len(), max_concurrent_generations);
+                // Requests always have a slot of their own.
+                assert!(!requests.is_empty());
+                if pool_size > 0 && max_concurrent_generations > 1 {
+                    assert!(!refills.is_empty());
+                }
+            }
+        }
+    }
+}